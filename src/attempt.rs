@@ -0,0 +1,127 @@
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+
+use crate::course::CourseSelectionResponseBody;
+
+/// Bir ders seçim denemesinin sonucu. Kayıt günü yükü altında sunucu JSON yerine HTML hata
+/// sayfası, 502 ya da boş cevap dönebiliyor; bunlar çalıştırmayı durdurmaz.
+#[derive(Debug, Clone)]
+pub enum AttemptOutcome {
+    Response(CourseSelectionResponseBody),
+    RateLimited,
+    GatewayError(StatusCode),
+    Maintenance(StatusCode),
+    AuthExpired(StatusCode),
+    ParseFailure { status: StatusCode, excerpt: String },
+    Network(String),
+}
+
+impl AttemptOutcome {
+    const MAINTENANCE_MARKERS: &[&str] = &["bakım", "bakim", "maintenance"];
+
+    pub fn classify(status: StatusCode, body: &[u8]) -> Self {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return AttemptOutcome::RateLimited;
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return AttemptOutcome::AuthExpired(status);
+        }
+        if let Ok(body) = serde_json::from_slice(body) {
+            return AttemptOutcome::Response(body);
+        }
+
+        let text = String::from_utf8_lossy(body).to_lowercase();
+        if Self::MAINTENANCE_MARKERS.iter().any(|m| text.contains(m)) {
+            return AttemptOutcome::Maintenance(status);
+        }
+        if matches!(
+            status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ) {
+            return AttemptOutcome::GatewayError(status);
+        }
+
+        AttemptOutcome::ParseFailure {
+            status,
+            excerpt: excerpt(&String::from_utf8_lossy(body)),
+        }
+    }
+
+    /// Politikada bu denemeye uyan kuralların adları: cevap türü, HTTP durum kodu ve sonuç kodları
+    pub fn policy_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.kind().to_string()];
+        match self {
+            AttemptOutcome::Response(body) => keys.extend(body.result_codes()),
+            AttemptOutcome::RateLimited => keys.push("429".to_string()),
+            AttemptOutcome::GatewayError(status)
+            | AttemptOutcome::Maintenance(status)
+            | AttemptOutcome::AuthExpired(status)
+            | AttemptOutcome::ParseFailure { status, .. } => keys.push(status.as_u16().to_string()),
+            AttemptOutcome::Network(_) => (),
+        }
+        keys
+    }
+
+    /// Sunucu kayıt zamanı henüz gelmedi dedi
+    pub fn is_time_blocked(&self) -> bool {
+        self.response().is_some_and(|body| body.is_time_blocked())
+    }
+
+    /// Kayıtlarda kullanılan kısa ad
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AttemptOutcome::Response(_) if self.is_time_blocked() => "time_block",
+            AttemptOutcome::Response(_) => "response",
+            AttemptOutcome::RateLimited => "rate_limited",
+            AttemptOutcome::GatewayError(_) => "gateway_error",
+            AttemptOutcome::Maintenance(_) => "maintenance",
+            AttemptOutcome::AuthExpired(_) => "auth_expired",
+            AttemptOutcome::ParseFailure { .. } => "parse_failure",
+            AttemptOutcome::Network(_) => "network",
+        }
+    }
+
+    pub fn response(&self) -> Option<&CourseSelectionResponseBody> {
+        match self {
+            AttemptOutcome::Response(body) => Some(body),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AttemptOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttemptOutcome::Response(body) => write!(f, "{}", body),
+            AttemptOutcome::RateLimited => writeln!(f, "Çok fazla istek (HTTP 429)."),
+            AttemptOutcome::GatewayError(status) => {
+                writeln!(f, "Sunucu geçidi hatası (HTTP {}).", status)
+            }
+            AttemptOutcome::Maintenance(status) => {
+                writeln!(f, "Sistem bakımda görünüyor (HTTP {}).", status)
+            }
+            AttemptOutcome::AuthExpired(status) => {
+                writeln!(
+                    f,
+                    "API Token geçersiz ya da süresi dolmuş (HTTP {}).",
+                    status
+                )
+            }
+            AttemptOutcome::ParseFailure { status, excerpt } => {
+                writeln!(f, "Cevap okunamadı (HTTP {}): {}", status, excerpt)
+            }
+            AttemptOutcome::Network(e) => writeln!(f, "İstek gönderilemedi: {}", e),
+        }
+    }
+}
+
+/// Cevabın ilk birkaç yüz karakteri, neyin değiştiğini görmek için yeterli
+pub fn excerpt(body: &str) -> String {
+    const EXCERPT_LENGTH: usize = 200;
+    let body: String = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match body.char_indices().nth(EXCERPT_LENGTH) {
+        Some((i, _)) => format!("{}...", &body[..i]),
+        None => body,
+    }
+}
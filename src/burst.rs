@@ -0,0 +1,67 @@
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::session;
+
+/// Açılış etrafında gönderilen bir isteğin sonucu, sonraki dönemlerde ofsetleri ayarlamak için saklanır
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BurstRecord {
+    pub username: String,
+    pub opening: DateTime<FixedOffset>,
+    pub offset_ms: i64,
+    pub send_error_us: u128,
+    pub outcome: String,
+}
+
+/// Tüm çalıştırmaların açılış denemeleri satır satır JSON olarak tek bir dosyaya eklenir
+#[derive(Debug, Clone)]
+pub struct BurstLog {
+    path: PathBuf,
+}
+
+impl Default for BurstLog {
+    fn default() -> Self {
+        let dir = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("itu-keplerbot");
+
+        Self {
+            path: dir.join("bursts.jsonl"),
+        }
+    }
+}
+
+impl BurstLog {
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn append(&self, records: &[BurstRecord]) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&self.path)?;
+        // mode sadece dosya ilk oluşturulurken uygulanır, önceden oluşmuş dosyalar da kısıtlanır
+        session::make_private(&self.path)?;
+        for record in records {
+            writeln!(file, "{}", serde_json::to_string(record)?)?;
+        }
+        Ok(())
+    }
+}
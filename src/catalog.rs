@@ -0,0 +1,383 @@
+use std::{error::Error, fmt::Display, fs::File, path::Path};
+
+use chrono::{NaiveTime, Weekday};
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Bir şubenin haftalık tek bir ders saati
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Session {
+    pub day: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Session {
+    pub fn overlaps(&self, other: &Session) -> bool {
+        self.day == other.day && self.start < other.end && other.start < self.end
+    }
+}
+
+impl Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}-{}",
+            self.day,
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Section {
+    pub crn: String,
+    pub course_code: String,
+    pub course_title: String,
+    pub instructor: String,
+    pub sessions: Vec<Session>,
+    pub capacity: u32,
+    pub enrolled: u32,
+}
+
+impl Section {
+    pub fn conflicts_with(&self, other: &Section) -> bool {
+        self.sessions
+            .iter()
+            .any(|a| other.sessions.iter().any(|b| a.overlaps(b)))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.capacity > 0 && self.enrolled >= self.capacity
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CRN {} - {} ({}) [{}/{}]",
+            self.crn, self.course_code, self.instructor, self.enrolled, self.capacity
+        )?;
+        self.sessions.iter().try_for_each(|s| write!(f, " {}", s))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchCode {
+    #[serde(rename = "bransKoduId")]
+    id: i64,
+
+    #[serde(rename = "dersBransKodu")]
+    code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Catalog {
+    pub sections: Vec<Section>,
+}
+
+/// "BLG 102E", "blg102e" ve "BLG  102E" aynı ders kodu sayılır
+pub fn normalize_course_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn branch_of(course_code: &str) -> String {
+    course_code
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+fn parse_turkish_weekday(day: &str) -> Option<Weekday> {
+    match day.trim() {
+        "Pazartesi" => Some(Weekday::Mon),
+        "Salı" => Some(Weekday::Tue),
+        "Çarşamba" => Some(Weekday::Wed),
+        "Perşembe" => Some(Weekday::Thu),
+        "Cuma" => Some(Weekday::Fri),
+        "Cumartesi" => Some(Weekday::Sat),
+        "Pazar" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// "0830/1129" biçimindeki saat aralığını ayrıştırır
+fn parse_time_range(range: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = range.trim().split_once('/')?;
+    let start = NaiveTime::parse_from_str(start, "%H%M").ok()?;
+    let end = NaiveTime::parse_from_str(end, "%H%M").ok()?;
+    Some((start, end))
+}
+
+fn cell_lines(cell: &ElementRef) -> Vec<String> {
+    cell.text()
+        .flat_map(|t| t.split_whitespace())
+        .map(String::from)
+        .collect()
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell_lines(cell).join(" ")
+}
+
+impl Catalog {
+    const BRANCH_CODES_URL: &str = "https://obs.itu.edu.tr/public/DersProgram/SearchBransKoduByProgramSeviye?programSeviyeTipiAnahtari=LS";
+    const COURSE_SCHEDULE_URL: &str = "https://obs.itu.edu.tr/public/DersProgram/DersProgramSearch?programSeviyeTipiAnahtari=LS&dersBransKoduId=";
+
+    pub fn from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Sadece istenen derslerin branşlarını OBS'nin herkese açık ders programından çeker
+    pub async fn fetch(client: &Client, course_codes: &[String]) -> Result<Self, Box<dyn Error>> {
+        let branch_codes: Vec<BranchCode> = client
+            .get(Self::BRANCH_CODES_URL)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let mut wanted_branches: Vec<String> = course_codes.iter().map(|c| branch_of(c)).collect();
+        wanted_branches.sort();
+        wanted_branches.dedup();
+
+        let mut sections = Vec::new();
+        for branch in wanted_branches {
+            let Some(branch_code) = branch_codes.iter().find(|b| b.code == branch) else {
+                return Err(format!("{} branş kodu ders programında bulunamadı", branch).into());
+            };
+
+            let url = format!("{}{}", Self::COURSE_SCHEDULE_URL, branch_code.id);
+            let body = client.get(url).send().await?.text().await?;
+            let (parsed, skipped) = Self::parse_schedule_page(&Html::parse_document(&body));
+            for reason in skipped {
+                println!("{} branşında bir satır atlandı: {}", branch, reason);
+            }
+            sections.extend(parsed);
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Şubeleri ve okunamadığı için atlanan satırların nedenlerini döndürür
+    fn parse_schedule_page(document: &Html) -> (Vec<Section>, Vec<String>) {
+        let row_selector = Selector::parse("table tbody tr").expect("row selector");
+        let cell_selector = Selector::parse("td").expect("cell selector");
+
+        let mut sections = Vec::new();
+        let mut skipped = Vec::new();
+        for row in document.select(&row_selector) {
+            let cells: Vec<ElementRef> = row.select(&cell_selector).collect();
+            // CRN, Ders Kodu, Ders Adı, Öğretim Yöntemi, Eğitmen, Bina, Gün, Saat, Derslik, Kontenjan, Yazılan, ...
+            if cells.len() < 11 {
+                continue;
+            }
+
+            // 0 kontenjan sınırsız sayıldığı için okunamayan sayı 0 yapılmaz, satır atlanır
+            let crn = cell_text(&cells[0]);
+            let (capacity, enrolled) = (cell_text(&cells[9]), cell_text(&cells[10]));
+            let (Ok(capacity), Ok(enrolled)) = (capacity.parse(), enrolled.parse()) else {
+                skipped.push(format!(
+                    "CRN {}: kontenjan ({:?}) ya da yazılan ({:?}) sayı değil",
+                    crn, capacity, enrolled
+                ));
+                continue;
+            };
+
+            let days = cell_lines(&cells[6]);
+            let times = cell_lines(&cells[7]);
+            let sessions = days
+                .iter()
+                .zip(times.iter())
+                .filter_map(|(day, time)| {
+                    let day = parse_turkish_weekday(day)?;
+                    let (start, end) = parse_time_range(time)?;
+                    Some(Session { day, start, end })
+                })
+                .collect();
+
+            sections.push(Section {
+                crn,
+                course_code: cell_text(&cells[1]),
+                course_title: cell_text(&cells[2]),
+                instructor: cell_text(&cells[4]),
+                sessions,
+                capacity,
+                enrolled,
+            });
+        }
+
+        (sections, skipped)
+    }
+
+    pub fn sections_of<'a>(&'a self, course_code: &str) -> Vec<&'a Section> {
+        let wanted = normalize_course_code(course_code);
+        self.sections
+            .iter()
+            .filter(|s| normalize_course_code(&s.course_code) == wanted)
+            .collect()
+    }
+
+    pub fn find_crn(&self, crn: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.crn == crn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// OBS ders programı sayfasının sadeleştirilmiş hali, sütun sırası sayfadakiyle aynı
+    const SCHEDULE_PAGE: &str = r#"
+        <html><body><table>
+          <thead><tr>
+            <th>CRN</th><th>Ders Kodu</th><th>Ders Adı</th><th>Öğretim Yöntemi</th><th>Eğitmen</th>
+            <th>Bina</th><th>Gün</th><th>Saat</th><th>Derslik</th><th>Kontenjan</th><th>Yazılan</th>
+            <th>Rezervasyon</th>
+          </tr></thead>
+          <tbody>
+            <tr>
+              <td>21340</td><td>BLG 102E</td><td>Introduction to Scientific and Engineering Computing</td>
+              <td>Yüz Yüze</td><td>Ali  Veli</td><td>EEB<br>EEB</td>
+              <td>Pazartesi<br>Çarşamba</td><td>0830/1129<br>1330/1529</td>
+              <td>5202<br>5204</td><td>60</td><td>60</td><td>-</td>
+            </tr>
+            <tr>
+              <td>21341</td><td>BLG 102E</td><td>Introduction to Scientific and Engineering Computing</td>
+              <td>Çevrimiçi</td><td>Ayşe Yılmaz</td><td>--</td>
+              <td>Perşembe</td><td>1430/1629</td>
+              <td>--</td><td>80</td><td>12</td><td>-</td>
+            </tr>
+            <tr>
+              <td>21342</td><td>BLG 102E</td><td>Introduction to Scientific and Engineering Computing</td>
+              <td>Uzaktan</td><td>-</td><td>--</td>
+              <td>----</td><td>----</td>
+              <td>--</td><td>0</td><td>0</td><td>-</td>
+            </tr>
+            <tr><td colspan="11">Kayıt bulunamadı</td></tr>
+          </tbody>
+        </table></body></html>
+    "#;
+
+    fn sections() -> Vec<Section> {
+        let (sections, skipped) =
+            Catalog::parse_schedule_page(&Html::parse_document(SCHEDULE_PAGE));
+        assert!(skipped.is_empty(), "{:?}", skipped);
+        sections
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_rows_by_column() {
+        let sections = sections();
+        assert_eq!(sections.len(), 3);
+
+        let first = &sections[0];
+        assert_eq!(first.crn, "21340");
+        assert_eq!(first.course_code, "BLG 102E");
+        assert_eq!(
+            first.course_title,
+            "Introduction to Scientific and Engineering Computing"
+        );
+        assert_eq!(first.instructor, "Ali Veli");
+        assert_eq!((first.capacity, first.enrolled), (60, 60));
+        assert!(first.is_full());
+    }
+
+    #[test]
+    fn pairs_days_with_time_ranges() {
+        let sections = sections();
+        assert_eq!(
+            sections[0].sessions,
+            vec![
+                Session {
+                    day: Weekday::Mon,
+                    start: time(8, 30),
+                    end: time(11, 29)
+                },
+                Session {
+                    day: Weekday::Wed,
+                    start: time(13, 30),
+                    end: time(15, 29)
+                },
+            ]
+        );
+        assert_eq!(sections[1].sessions.len(), 1);
+        assert_eq!(sections[1].sessions[0].day, Weekday::Thu);
+    }
+
+    #[test]
+    fn unscheduled_and_unlimited_sections() {
+        let section = &sections()[2];
+        assert!(section.sessions.is_empty());
+        assert_eq!(section.capacity, 0);
+        assert!(!section.is_full());
+    }
+
+    #[test]
+    fn course_codes_are_normalized() {
+        let catalog = Catalog {
+            sections: sections(),
+        };
+        assert_eq!(catalog.sections_of("blg102e").len(), 3);
+        assert_eq!(catalog.sections_of(" BLG  102E ").len(), 3);
+        assert!(catalog.sections_of("BLG 101E").is_empty());
+        assert_eq!(branch_of("blg102e"), "BLG");
+        assert!(catalog.find_crn("21341").is_some());
+    }
+
+    #[test]
+    fn sessions_overlap_only_on_the_same_day() {
+        let monday = Session {
+            day: Weekday::Mon,
+            start: time(8, 30),
+            end: time(11, 29),
+        };
+        let later = Session {
+            start: time(11, 30),
+            end: time(12, 29),
+            ..monday.clone()
+        };
+        let inside = Session {
+            start: time(10, 30),
+            end: time(12, 29),
+            ..monday.clone()
+        };
+        let tuesday = Session {
+            day: Weekday::Tue,
+            ..monday.clone()
+        };
+
+        assert!(monday.overlaps(&inside));
+        assert!(inside.overlaps(&monday));
+        assert!(!monday.overlaps(&later));
+        assert!(!monday.overlaps(&tuesday));
+    }
+
+    #[test]
+    fn rows_with_unreadable_counts_are_skipped() {
+        let page = SCHEDULE_PAGE
+            .replace("<td>80</td><td>12</td>", "<td>80</td><td>12 / 80</td>")
+            .replace("<td>60</td><td>60</td>", "<td></td><td>60</td>");
+        let (sections, skipped) = Catalog::parse_schedule_page(&Html::parse_document(&page));
+
+        assert_eq!(
+            sections.iter().map(|s| s.crn.as_str()).collect::<Vec<_>>(),
+            vec!["21342"]
+        );
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[0].contains("21340"), "{}", skipped[0]);
+        assert!(skipped[1].contains("21341"), "{}", skipped[1]);
+    }
+}
@@ -1,7 +1,13 @@
-use std::{path::PathBuf, string::ParseError, time::SystemTime};
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
 
-use clap::{arg, command, Args, Parser, Subcommand};
-use humantime::parse_rfc3339_weak;
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc, Weekday};
+use clap::{Args, Parser, Subcommand};
+
+use crate::{report::ReportFormat, time_input};
 
 #[derive(Parser, Debug)]
 pub struct Cli {
@@ -16,13 +22,28 @@ pub enum Command {
 
     #[command(about = "Run the bot")]
     Run(RunArgs), // TODO: add override args
-}
 
-fn parse_time(arg: &str) -> Result<std::time::Duration, ParseError> {
-    // TODO: Error handling
-    let time = parse_rfc3339_weak(arg).unwrap();
+    #[command(about = "Find a conflict-free schedule and write its CRNs into a config file")]
+    Plan(PlanArgs),
+
+    #[command(about = "Show the courses the account is currently registered for")]
+    Status(StatusArgs),
+
+    #[command(about = "Add courses right now, without waiting for a registration time")]
+    Add(SelectNowArgs),
+
+    #[command(about = "Drop courses right now, without waiting for a registration time")]
+    Drop(DropArgs),
 
-    Ok(time.duration_since(SystemTime::UNIX_EPOCH).unwrap())
+    #[command(about = "Check whether the ITU pages the bot relies on have changed")]
+    Doctor(DoctorArgs),
+
+    #[command(about = "List past runs or inspect one of them")]
+    History(HistoryArgs),
+}
+
+pub fn parse_time(arg: &str) -> Result<DateTime<FixedOffset>, String> {
+    time_input::parse(arg, Utc::now())
 }
 
 #[derive(Args, Debug, Clone)]
@@ -30,24 +51,308 @@ pub struct MakeConfigArgs {
     #[arg(short = 'o', long = "output", help = "Output path")]
     pub output_path: Option<PathBuf>,
 
-    #[arg(short = 'u', long = "username", help = "ITÜ Kullanıcı Adı")]
-    pub username: String,
+    #[arg(short = 'i', long = "interactive", help = "Bilgileri adım adım sor")]
+    pub interactive: bool,
 
-    #[arg(short = 'p', long = "password", help = "ITÜ Kullanıcı Şifresi")]
-    pub password: String,
+    #[arg(
+        short = 'u',
+        long = "username",
+        help = "ITÜ Kullanıcı Adı",
+        required_unless_present = "interactive"
+    )]
+    pub username: Option<String>,
 
-    #[arg(short = 't', long = "time", help = "Ders Seçim Zamanı", value_parser = parse_time)]
-    pub time: std::time::Duration,
+    #[arg(
+        short = 'p',
+        long = "password",
+        help = "ITÜ Kullanıcı Şifresi",
+        required_unless_present = "interactive"
+    )]
+    pub password: Option<String>,
+
+    #[arg(
+        short = 't',
+        long = "time",
+        help = "Ders Seçim Zamanı (örn. \"2025-02-10 14:00\", \"10.02.2025 14:00 Europe/Istanbul\", \"yarın 14:00\", \"in 10m\")",
+        value_parser = parse_time,
+        required_unless_present = "interactive"
+    )]
+    pub time: Option<DateTime<FixedOffset>>,
 
     #[arg(long = "crn", help = "Eklenecek CRN'ler", value_delimiter = ',')]
     pub crn_list: Vec<String>,
 
     #[arg(long = "scrn", help = "Çıkartılacak CRN'ler", value_delimiter = ',')]
     pub scrn_list: Vec<String>,
+
+    #[arg(
+        long = "catalog",
+        help = "CRN'leri kontrol etmek için kullanılacak ders programı JSON dosyası"
+    )]
+    pub catalog_path: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
 pub struct RunArgs {
     #[arg(short = 'c', long = "config", help = "config.json dosyasının konumu")]
     pub config_path: Option<PathBuf>,
+
+    #[arg(
+        long = "dry-run",
+        help = "Ders seçimini göndermez, gönderilecek isteği ve zamanlamayı yazdırır"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long = "with-login",
+        requires = "dry_run",
+        help = "--dry-run ile birlikte giriş yapıp API Token da alınır"
+    )]
+    pub with_login: bool,
+
+    #[arg(
+        long = "confirm",
+        help = "Plan yazdırıldıktan sonra devam etmeden önce onay ister"
+    )]
+    pub confirm: bool,
+
+    #[arg(
+        long = "login-lead",
+        help = "Girişin ders seçiminden ne kadar önce yapılacağı (örn. \"1m\", \"90s\")",
+        value_parser = humantime::parse_duration,
+        default_value = "1m"
+    )]
+    pub login_lead: Duration,
+
+    #[arg(
+        long = "login-retries",
+        help = "Giriş başarısız olursa kaç kere daha denenecek",
+        default_value_t = 3
+    )]
+    pub login_retries: u32,
+
+    #[arg(
+        long = "hedge-after",
+        help = "Ders seçim isteği bu sürede cevap vermezse yeni bir bağlantıdan ek istek gönderilir (örn. \"300ms\")",
+        value_parser = humantime::parse_duration
+    )]
+    pub hedge_after: Option<Duration>,
+
+    #[arg(
+        long = "max-hedges",
+        help = "Bir pencerede gönderilebilecek toplam ek istek sayısı (zamana yayılmaz, bitince ek istek gönderilmez)",
+        requires = "hedge_after",
+        default_value_t = 5
+    )]
+    pub max_hedges: u32,
+
+    #[arg(
+        long = "burst",
+        help = "Açılışa göre ms cinsinden istek ofsetleri, ardından normal aralıkla devam edilir (örn. --burst=-150,0,300)",
+        value_delimiter = ',',
+        allow_negative_numbers = true
+    )]
+    pub burst: Vec<i64>,
+
+    #[arg(
+        long = "probe",
+        help = "Denemelere açılıştan önce başlayıp sunucunun gerçekte ne zaman açıldığını algılar",
+        conflicts_with = "burst"
+    )]
+    pub probe: bool,
+
+    #[arg(
+        long = "report",
+        help = "Her pencerenin sonunda yazılacak raporun biçimi (markdown, html)",
+        value_parser = ReportFormat::parse,
+        default_value = "markdown"
+    )]
+    pub report_format: ReportFormat,
+
+    #[command(flatten)]
+    pub session_args: SessionArgs,
+}
+
+fn parse_clock_time(arg: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(arg, "%H:%M")
+        .map_err(|_| format!("{} saati SS:DD biçiminde değil", arg))
+}
+
+fn parse_weekday(arg: &str) -> Result<Weekday, String> {
+    match arg.to_lowercase().as_str() {
+        "pazartesi" | "pzt" => Ok(Weekday::Mon),
+        "salı" | "sali" => Ok(Weekday::Tue),
+        "çarşamba" | "carsamba" | "çrş" => Ok(Weekday::Wed),
+        "perşembe" | "persembe" | "prş" => Ok(Weekday::Thu),
+        "cuma" => Ok(Weekday::Fri),
+        "cumartesi" => Ok(Weekday::Sat),
+        "pazar" => Ok(Weekday::Sun),
+        other => other
+            .parse()
+            .map_err(|_| format!("{} bir gün adı değil", arg)),
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct PlanArgs {
+    #[arg(
+        short = 'c',
+        long = "config",
+        help = "Güncellenecek config.json dosyasının konumu"
+    )]
+    pub config_path: Option<PathBuf>,
+
+    #[arg(
+        long = "course",
+        help = "Alınmak istenen ders kodları (örn. \"BLG 102E\")",
+        value_delimiter = ',',
+        required = true
+    )]
+    pub course_codes: Vec<String>,
+
+    #[arg(long = "not-before", help = "Bu saatten önce ders istenmiyor (SS:DD)", value_parser = parse_clock_time)]
+    pub not_before: Option<NaiveTime>,
+
+    #[arg(long = "free-day", help = "Boş kalması istenen günler", value_delimiter = ',', value_parser = parse_weekday)]
+    pub free_days: Vec<Weekday>,
+
+    #[arg(
+        long = "instructor",
+        help = "Tercih edilen eğitmenler",
+        value_delimiter = ','
+    )]
+    pub preferred_instructors: Vec<String>,
+
+    #[arg(
+        long = "catalog",
+        help = "OBS yerine kullanılacak ders programı JSON dosyası"
+    )]
+    pub catalog_path: Option<PathBuf>,
+
+    #[arg(
+        long = "alternatives",
+        help = "Config'e yazılacak alternatif program sayısı",
+        default_value_t = 3
+    )]
+    pub alternative_count: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    #[arg(short = 'c', long = "config", help = "config.json dosyasının konumu")]
+    pub config_path: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub session_args: SessionArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct SelectNowArgs {
+    #[arg(short = 'c', long = "config", help = "config.json dosyasının konumu")]
+    pub config_path: Option<PathBuf>,
+
+    #[arg(help = "İşlem yapılacak CRN'ler", required = true)]
+    pub crn_list: Vec<String>,
+
+    #[arg(
+        long = "retry",
+        help = "En fazla kaç kere denenecek (en az 1)",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub try_count: u64,
+
+    #[command(flatten)]
+    pub session_args: SessionArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct DropArgs {
+    #[command(flatten)]
+    pub select_now_args: SelectNowArgs,
+
+    #[arg(short = 'y', long = "yes", help = "Onay sormadan bırak")]
+    pub yes: bool,
+}
+
+/// Kullanıcıya evet/hayır sorusu sorar, sadece "e" veya "evet" onay sayılır
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{} [e/H]: ", prompt);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(
+        answer.trim().to_lowercase().as_str(),
+        "e" | "evet"
+    ))
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct SessionArgs {
+    #[arg(
+        long = "reuse-session",
+        help = "Geçerliyse kayıtlı oturumu kullan, giriş yapma"
+    )]
+    pub reuse_session: bool,
+
+    #[arg(
+        long = "cookies",
+        help = "Tarayıcıdan dışa aktarılmış Netscape cookies.txt dosyası, giriş yapılmaz"
+    )]
+    pub cookies_path: Option<PathBuf>,
+
+    #[arg(
+        long = "jwt",
+        help = "API Token dosyası ('-' ise standart girdiden okunur), giriş yapılmaz"
+    )]
+    pub jwt_path: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    #[arg(
+        short = 'c',
+        long = "config",
+        help = "config.json dosyasının konumu, giriş gerektiren kontroller için"
+    )]
+    pub config_path: Option<PathBuf>,
+
+    #[arg(
+        long = "send-selection",
+        help = "Ders seçim adresine boş bir istek de gönder (3 saniye sınırına ve geçici engellere sayılır)"
+    )]
+    pub send_selection: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    #[arg(
+        help = "Ayrıntıları gösterilecek çalıştırmanın numarası, verilmezse son çalıştırmalar listelenir"
+    )]
+    pub run_id: Option<i64>,
+
+    #[arg(
+        short = 'n',
+        long = "limit",
+        help = "Listelenecek en fazla çalıştırma sayısı",
+        default_value_t = 20
+    )]
+    pub limit: usize,
+
+    #[arg(
+        long = "report",
+        help = "Çalıştırmanın raporunu bu biçimde (markdown, html) yeniden yaz",
+        value_parser = ReportFormat::parse,
+        requires = "run_id"
+    )]
+    pub report_format: Option<ReportFormat>,
+
+    #[arg(
+        short = 'c',
+        long = "config",
+        help = "Rapordan parolası silinecek config.json dosyasının konumu"
+    )]
+    pub config_path: Option<PathBuf>,
 }
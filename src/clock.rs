@@ -0,0 +1,120 @@
+use std::{
+    error::Error,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use reqwest::{header::DATE, Client};
+use tokio::time::sleep;
+
+use crate::requester::Config;
+
+/// Sunucu saati ile yerel saat arasındaki fark. Tüm profiller aynı farkı kullanır.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ServerClock {
+    offset: TimeDelta,
+}
+
+/// Tek bir HEAD isteğinde okunan sunucu saati ve isteğin yerel saatteki orta noktası
+#[derive(Debug, Clone, Copy)]
+struct DateSample {
+    server: DateTime<Utc>,
+    local: DateTime<Utc>,
+}
+
+impl ServerClock {
+    const SYNC_URL: &str = "https://obs.itu.edu.tr";
+    const SYNC_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(5);
+    /// `Date` başlığının saniyesi değişene kadar en fazla bu kadar örnek alınır
+    const SYNC_WINDOW: TimeDelta = TimeDelta::milliseconds(2500);
+    /// Saniye değişimi yakalanamazsa aşağı yuvarlamanın ortalama etkisi
+    const ROUNDING_CORRECTION: TimeDelta = TimeDelta::milliseconds(500);
+
+    /// Saat ölçümü takılırsa hiçbir profil planlanamaz, bu yüzden kısa süre sınırları var
+    pub fn client() -> Client {
+        Client::builder()
+            .connect_timeout(Self::SYNC_CONNECT_TIMEOUT)
+            .timeout(Self::SYNC_TIMEOUT)
+            .build()
+            .expect("Client::builder()")
+    }
+
+    /// Sunucunun `Date` başlığına bakarak farkı ölçer. Başlık saniye hassasiyetinde ve aşağı
+    /// yuvarlanmış olduğundan saniyenin değiştiği an yakalanana kadar arka arkaya istek gönderilir;
+    /// fark böylece iki isteğin arası kadar hassas olur.
+    pub async fn sync(client: &Client) -> Result<Self, Box<dyn Error>> {
+        let first = Self::sample(client).await?;
+        let mut previous = first;
+
+        while previous.local - first.local < Self::SYNC_WINDOW {
+            let sample = Self::sample(client).await?;
+            if sample.server > previous.server {
+                // sunucu yeni saniyeye iki isteğin arasında geçti
+                let boundary = previous.local + (sample.local - previous.local) / 2;
+                return Ok(Self {
+                    offset: sample.server - boundary,
+                });
+            }
+            previous = sample;
+        }
+
+        Ok(Self {
+            offset: first.server + Self::ROUNDING_CORRECTION - first.local,
+        })
+    }
+
+    async fn sample(client: &Client) -> Result<DateSample, Box<dyn Error>> {
+        let sent_at = Utc::now();
+        let res = client.head(Self::SYNC_URL).send().await?;
+        let received_at = Utc::now();
+
+        let date = res
+            .headers()
+            .get(DATE)
+            .ok_or("Sunucu Date başlığı göndermedi")?
+            .to_str()?;
+
+        Ok(DateSample {
+            server: DateTime::parse_from_rfc2822(date)?.with_timezone(&Utc),
+            local: sent_at + (received_at - sent_at) / 2,
+        })
+    }
+
+    pub fn offset(&self) -> TimeDelta {
+        self.offset
+    }
+
+    /// Sunucu saatine göre verilen zamanın yerel saatteki karşılığı
+    pub fn local_time(&self, server_time: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        server_time - self.offset
+    }
+
+    pub fn now_trt(&self) -> DateTime<FixedOffset> {
+        (Utc::now() + self.offset).with_timezone(&Config::TRT_TIMEZONE)
+    }
+}
+
+/// Hedef ana kadar uyur. İşletim sisteminin zamanlayıcısı birkaç milisaniye geç uyandırabildiği için
+/// son kısım monoton saate bakılarak döngüde beklenir. Döngü ayrı bir thread'de döner; aynı görevde
+/// bekleyen diğer profiller ve açılış denemeleri bu sırada da çalışmaya devam eder. Hedeflenen anı
+/// döndürür.
+pub async fn sleep_precise(duration: Duration) -> Instant {
+    const SPIN_WINDOW: Duration = Duration::from_millis(5);
+
+    let deadline = Instant::now() + duration;
+    if let Some(coarse) = duration.checked_sub(SPIN_WINDOW) {
+        sleep(coarse).await;
+    }
+    let spin = tokio::task::spawn_blocking(move || {
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+    });
+    if spin.await.is_err() {
+        // döngü çalıştırılamadıysa kalan kısım normal uykuyla beklenir
+        sleep(deadline.saturating_duration_since(Instant::now())).await;
+    }
+
+    deadline
+}
@@ -3,7 +3,7 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::requester::Config;
+use crate::requester::Job;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CourseSelectionRequestBody {
@@ -23,9 +23,9 @@ impl CourseSelectionRequestBody {
     }
 }
 
-impl From<Config> for CourseSelectionRequestBody {
-    fn from(config: Config) -> Self {
-        Self::new(config.crn_list, config.scrn_list)
+impl From<Job> for CourseSelectionRequestBody {
+    fn from(job: Job) -> Self {
+        Self::new(job.crn_list, job.scrn_list)
     }
 }
 
@@ -182,6 +182,13 @@ impl KnownResultCode {
     }
 }
 
+/// Sunucunun gönderdiği ham kodun (ör. "VAL03") açıklaması, bilinmeyen kodlar için `None`
+pub fn describe_code(code: &str) -> Option<&'static str> {
+    serde_json::from_value::<KnownResultCode>(Value::String(code.to_string()))
+        .ok()
+        .map(|known| known.description())
+}
+
 impl Display for KnownResultCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.description())
@@ -204,6 +211,56 @@ enum ResultCode {
     Unknown(UnknownResultCode),
 }
 
+impl ResultCode {
+    fn is_success(&self) -> bool {
+        matches!(
+            self,
+            ResultCode::Known(
+                KnownResultCode::SuccessResult
+                    | KnownResultCode::SuccessfullyAdded
+                    | KnownResultCode::SuccessfullyDropped
+            )
+        )
+    }
+
+    /// Sunucunun gönderdiği ham kod, ör. "VAL13"
+    fn code(&self) -> String {
+        let value = match self {
+            ResultCode::Known(k) => serde_json::to_value(k).unwrap_or(Value::Null),
+            ResultCode::Unknown(UnknownResultCode(v)) => v.clone(),
+        };
+        match value {
+            Value::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+
+    /// Ekleme için tekrar denemeye gerek kalmadı mı: başarılı ya da ders zaten alınmış
+    fn settles_add(&self) -> bool {
+        self.is_success()
+            || matches!(
+                self,
+                ResultCode::Known(KnownResultCode::AlreadyAddedThisTerm)
+            )
+    }
+
+    /// Bırakma için tekrar denemeye gerek kalmadı mı: başarılı ya da derse zaten kayıtlı değil
+    fn settles_drop(&self) -> bool {
+        self.is_success()
+            || matches!(
+                self,
+                ResultCode::Known(KnownResultCode::CourseNotRegisteredNoOp)
+            )
+    }
+
+    fn is_time_block(&self) -> bool {
+        matches!(
+            self,
+            ResultCode::Known(KnownResultCode::TimeBlock1 | KnownResultCode::TimeBlock2)
+        )
+    }
+}
+
 impl Display for ResultCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -271,6 +328,56 @@ pub struct CourseSelectionResponseBody {
     scrn_result_list: Vec<CrnDropResult>,
 }
 
+impl CourseSelectionResponseBody {
+    /// Cevaptaki tüm CRN'lerin ham sonuç kodları
+    pub fn result_codes(&self) -> Vec<String> {
+        self.ecrn_result_list
+            .iter()
+            .map(|r| r.result_code.code())
+            .chain(self.scrn_result_list.iter().map(|r| r.result_code.code()))
+            .collect()
+    }
+
+    /// Eklenen her CRN için (CRN, ham kod, tekrar denemeye gerek yok mu)
+    pub fn add_results(&self) -> Vec<(String, String, bool)> {
+        self.ecrn_result_list
+            .iter()
+            .map(|r| {
+                (
+                    r.crn.clone(),
+                    r.result_code.code(),
+                    r.result_code.settles_add(),
+                )
+            })
+            .collect()
+    }
+
+    /// Bırakılan her CRN için (CRN, ham kod, tekrar denemeye gerek yok mu)
+    pub fn drop_results(&self) -> Vec<(String, String, bool)> {
+        self.scrn_result_list
+            .iter()
+            .map(|r| {
+                (
+                    r.crn.clone(),
+                    r.result_code.code(),
+                    r.result_code.settles_drop(),
+                )
+            })
+            .collect()
+    }
+
+    /// Sunucu kayıt zamanı gelmedi diyorsa istek erken gönderilmiştir
+    pub fn is_time_blocked(&self) -> bool {
+        self.ecrn_result_list
+            .iter()
+            .any(|r| r.result_code.is_time_block())
+            || self
+                .scrn_result_list
+                .iter()
+                .any(|r| r.result_code.is_time_block())
+    }
+}
+
 impl Display for CourseSelectionResponseBody {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Eklenen CRN Sonuçları")?;
@@ -286,3 +393,148 @@ impl Display for CourseSelectionResponseBody {
         Ok(())
     }
 }
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "CRN yazı veya sayı olmalı: {}",
+            other
+        ))),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegisteredCourse {
+    #[serde(deserialize_with = "string_or_number")]
+    pub crn: String,
+
+    #[serde(rename = "dersKodu", default)]
+    course_code: String,
+
+    #[serde(rename = "dersAdi", default)]
+    course_title: String,
+
+    #[serde(rename = "ogretimUyesi", default)]
+    instructor: String,
+
+    #[serde(rename = "kredi", default)]
+    credit: Option<f64>,
+}
+
+impl Display for RegisteredCourse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CRN {}: {} - {} ({})",
+            self.crn, self.course_code, self.course_title, self.instructor
+        )?;
+        if let Some(credit) = self.credit {
+            write!(f, " [{} kredi]", credit)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegisteredCoursesResponseBody {
+    #[serde(rename = "donem", default)]
+    term: String,
+
+    #[serde(rename = "dersListesi")]
+    courses: Vec<RegisteredCourse>,
+}
+
+impl RegisteredCoursesResponseBody {
+    pub fn contains(&self, crn: &str) -> bool {
+        self.courses.iter().any(|c| c.crn == crn)
+    }
+
+    pub fn len(&self) -> usize {
+        self.courses.len()
+    }
+}
+
+impl Display for RegisteredCoursesResponseBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} Dönemi Kayıtlı Dersler", self.term)?;
+        if self.courses.is_empty() {
+            writeln!(f, "Kayıtlı ders yok.")?;
+        }
+        self.courses.iter().try_for_each(|c| writeln!(f, "{}", c))
+    }
+}
+
+/// Ders seçimi sonrası kayıtlı derslere bakılarak her CRN'in son durumu
+#[derive(Debug, Clone, Default)]
+pub struct RegistrationReport {
+    added: Vec<(String, bool)>,
+    dropped: Vec<(String, bool)>,
+    /// Kayıtlı derslere bakılmadı, sonuç deneme cevaplarından çıkarıldı
+    from_attempts: bool,
+}
+
+impl RegistrationReport {
+    pub fn new(
+        registered: &RegisteredCoursesResponseBody,
+        crn_list: &[String],
+        scrn_list: &[String],
+    ) -> Self {
+        Self {
+            added: crn_list
+                .iter()
+                .map(|crn| (crn.clone(), registered.contains(crn)))
+                .collect(),
+            dropped: scrn_list
+                .iter()
+                .map(|crn| (crn.clone(), !registered.contains(crn)))
+                .collect(),
+            from_attempts: false,
+        }
+    }
+
+    pub fn from_attempts(added: Vec<(String, bool)>, dropped: Vec<(String, bool)>) -> Self {
+        Self {
+            added,
+            dropped,
+            from_attempts: true,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.added.iter().chain(&self.dropped).all(|(_, ok)| *ok)
+    }
+
+    /// Eklenmesi istenen her CRN ve alınıp alınmadığı
+    pub fn added(&self) -> &[(String, bool)] {
+        &self.added
+    }
+
+    /// Bırakılması istenen her CRN ve bırakılıp bırakılmadığı
+    pub fn dropped(&self) -> &[(String, bool)] {
+        &self.dropped
+    }
+}
+
+impl Display for RegistrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.from_attempts {
+            writeln!(
+                f,
+                "(Kayıtlı dersler doğrulanamadı, sonuçlar deneme cevaplarına göre)"
+            )?;
+        }
+        self.added.iter().try_for_each(|(crn, ok)| match ok {
+            true => writeln!(f, "CRN {}: alındı", crn),
+            false => writeln!(f, "CRN {}: ALINAMADI", crn),
+        })?;
+        self.dropped.iter().try_for_each(|(crn, ok)| match ok {
+            true => writeln!(f, "CRN {}: bırakıldı", crn),
+            false => writeln!(f, "CRN {}: BIRAKILAMADI", crn),
+        })
+    }
+}
@@ -0,0 +1,274 @@
+use std::fmt::Display;
+
+use reqwest::Client;
+use scraper::Html;
+use serde_json::Value;
+
+use crate::{
+    attempt::excerpt,
+    course::CourseSelectionResponseBody,
+    login::{LoginForm, LoginFormError},
+    requester::Requester,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "GEÇTİ"),
+            CheckStatus::Fail => write!(f, "HATA "),
+            CheckStatus::Skip => write!(f, "ATLA "),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[{}] {}: {}", self.status, self.name, self.detail)?;
+        if let Some(hint) = &self.hint {
+            writeln!(f, "        İpucu: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+const LOGIN_HOST: &str = "girisv3.itu.edu.tr";
+
+const REDIRECT_CHECK: &str = "Giriş adresi";
+const FORM_CHECK: &str = "Giriş formu";
+const JWT_CHECK: &str = "Giriş ve API Token";
+const REGISTERED_CHECK: &str = "Kayıtlı dersler";
+const SHAPE_CHECK: &str = "Ders seçim cevabı";
+
+async fn check_login_page(checks: &mut Vec<Check>) {
+    let res = match Client::new().get(Requester::LOGIN_URL).send().await {
+        Ok(res) => res,
+        Err(e) => {
+            checks.push(Check::fail(
+                REDIRECT_CHECK,
+                format!("{} adresine ulaşılamadı: {}", Requester::LOGIN_URL, e),
+                "İnternet bağlantınızı veya İTÜ sistemlerinin durumunu kontrol edin.",
+            ));
+            checks.push(Check::skip(FORM_CHECK, "giriş sayfası alınamadı"));
+            return;
+        }
+    };
+
+    let final_url = res.url().clone();
+    if final_url.host_str() == Some(LOGIN_HOST) {
+        checks.push(Check::pass(REDIRECT_CHECK, format!("{}", final_url)));
+    } else {
+        checks.push(Check::fail(
+            REDIRECT_CHECK,
+            format!(
+                "{} yönlendirmesi beklenmedik bir yere gidiyor: {}",
+                Requester::LOGIN_URL,
+                final_url
+            ),
+            format!(
+                "Giriş artık {} üzerinden yapılmıyor olabilir, giriş akışı değişmiş.",
+                LOGIN_HOST
+            ),
+        ));
+    }
+
+    let body = match res.text().await {
+        Ok(body) => body,
+        Err(e) => {
+            checks.push(Check::fail(
+                FORM_CHECK,
+                format!("sayfa okunamadı: {}", e),
+                "Tekrar deneyin.",
+            ));
+            return;
+        }
+    };
+
+    match LoginForm::parse(&Html::parse_document(&body)) {
+        Ok(form) => checks.push(Check::pass(
+            FORM_CHECK,
+            format!("{} alan bulundu, beklenen alanların hepsi var", form.fields().len()),
+        )),
+        Err(e @ LoginFormError::FormNotFound { .. }) => checks.push(Check::fail(
+            FORM_CHECK,
+            e.to_string(),
+            "Sayfa artık ASP.NET WebForms formu kullanmıyor olabilir. Tarayıcı oturumunu --cookies veya --jwt ile verebilirsiniz.",
+        )),
+        Err(e @ LoginFormError::MissingFields { .. }) => checks.push(Check::fail(
+            FORM_CHECK,
+            e.to_string(),
+            "Alan adları değişmiş olabilir (ctl00$ContentPlaceHolder1$...). Tarayıcı oturumunu --cookies veya --jwt ile verebilirsiniz.",
+        )),
+    }
+}
+
+async fn check_jwt(requester: &Requester, checks: &mut Vec<Check>) -> Option<String> {
+    if let Err(e) = requester.login().await {
+        checks.push(Check::fail(
+            JWT_CHECK,
+            format!("giriş yapılamadı: {}", e),
+            "Kullanıcı adı ve şifrenizi, ardından giriş formu kontrolünü inceleyin.",
+        ));
+        return None;
+    }
+
+    match requester.fetch_jwt().await {
+        Ok(jwt) => {
+            checks.push(Check::pass(JWT_CHECK, "token alındı ve çözümlenebildi"));
+            Some(jwt)
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                JWT_CHECK,
+                format!("token alınamadı: {}", e),
+                "Giriş başarısız olmuş (şifre yanlış olabilir), token adresi değişmiş ya da OBS'ye ulaşılamıyor olabilir.",
+            ));
+            None
+        }
+    }
+}
+
+/// Sadece okuyan bir istek; son durum kontrolünün dayandığı adresi ve cevap biçimini doğrular
+async fn check_registered_courses(requester: &Requester, jwt: &str, checks: &mut Vec<Check>) {
+    match requester.fetch_registered_courses(jwt).await {
+        Ok(registered) => checks.push(Check::pass(
+            REGISTERED_CHECK,
+            format!("{} kayıtlı ders, beklenen biçimde", registered.len()),
+        )),
+        Err(e) => checks.push(Check::fail(
+            REGISTERED_CHECK,
+            e.to_string(),
+            "Kayıtlı dersler adresi ya da cevabın biçimi (donem, dersListesi) değişmiş olabilir.",
+        )),
+    }
+}
+
+/// Canlı ders seçim adresine boş bir istek gönderir. Ders değişmez ama istek 3 saniye sınırına ve
+/// VAL13/VAL16 engellerine sayılır, bu yüzden sadece istenirse yapılır.
+async fn check_course_selection(requester: &Requester, jwt: &str, checks: &mut Vec<Check>) {
+    let res = match requester.send_empty_course_selection(jwt).await {
+        Ok(res) => res,
+        Err(e) => {
+            checks.push(Check::fail(
+                SHAPE_CHECK,
+                format!("istek gönderilemedi: {}", e),
+                "OBS'ye ulaşılamıyor olabilir.",
+            ));
+            return;
+        }
+    };
+
+    let status = res.status();
+    let body = match res.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            checks.push(Check::fail(
+                SHAPE_CHECK,
+                format!("cevap okunamadı: {}", e),
+                "Tekrar deneyin.",
+            ));
+            return;
+        }
+    };
+
+    if serde_json::from_slice::<CourseSelectionResponseBody>(&body).is_ok() {
+        checks.push(Check::pass(
+            SHAPE_CHECK,
+            format!("HTTP {}, beklenen biçimde", status),
+        ));
+        return;
+    }
+
+    let hint = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Object(fields)) => format!(
+            "Cevap JSON ama alanlar değişmiş. Gelen alanlar: {}. Beklenen: ecrnResultList, scrnResultList.",
+            fields.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+        Ok(_) => "Cevap JSON ama nesne değil, API biçimi değişmiş.".to_string(),
+        Err(_) => "Cevap JSON değil. Adres değişmiş (ör. v21 yerine yeni bir sürüm) ya da sistem bakımda olabilir.".to_string(),
+    };
+    checks.push(Check::fail(
+        SHAPE_CHECK,
+        format!(
+            "HTTP {}: {}",
+            status,
+            excerpt(&String::from_utf8_lossy(&body))
+        ),
+        hint,
+    ));
+}
+
+/// Botun dayandığı her varsayımı sırayla kontrol eder. Hesap bilgisi yoksa giriş gerektiren kontroller
+/// atlanır, ders seçim adresine sadece `send_selection` verilirse istek gönderilir.
+pub async fn run(requester: Option<&Requester>, send_selection: bool) -> Vec<Check> {
+    let mut checks = Vec::new();
+    check_login_page(&mut checks).await;
+
+    let Some(requester) = requester else {
+        checks.push(Check::skip(JWT_CHECK, "config dosyası okunamadı"));
+        checks.push(Check::skip(REGISTERED_CHECK, "config dosyası okunamadı"));
+        checks.push(Check::skip(SHAPE_CHECK, "config dosyası okunamadı"));
+        return checks;
+    };
+
+    let Some(jwt) = check_jwt(requester, &mut checks).await else {
+        checks.push(Check::skip(REGISTERED_CHECK, "API Token alınamadı"));
+        checks.push(Check::skip(SHAPE_CHECK, "API Token alınamadı"));
+        return checks;
+    };
+
+    check_registered_courses(requester, &jwt, &mut checks).await;
+    if send_selection {
+        check_course_selection(requester, &jwt, &mut checks).await;
+    } else {
+        checks.push(Check::skip(
+            SHAPE_CHECK,
+            "ders seçim adresine istek gönderilmedi, --send-selection ile yapılır",
+        ));
+    }
+
+    checks
+}
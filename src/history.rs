@@ -0,0 +1,585 @@
+use std::{error::Error, fmt::Display, fs, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, FixedOffset};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    attempt::AttemptOutcome,
+    course::RegistrationReport,
+    requester::{Config, Job},
+    session,
+    state::CrnAction,
+};
+
+/// Bir pencerenin nasıl bittiği
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunOutcome {
+    Complete,
+    Incomplete,
+    Failed,
+}
+
+impl RunOutcome {
+    fn name(&self) -> &'static str {
+        match self {
+            RunOutcome::Complete => "complete",
+            RunOutcome::Incomplete => "incomplete",
+            RunOutcome::Failed => "failed",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "complete" => Some(RunOutcome::Complete),
+            "incomplete" => Some(RunOutcome::Incomplete),
+            "failed" => Some(RunOutcome::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunOutcome::Complete => write!(f, "tamamlandı"),
+            RunOutcome::Incomplete => write!(f, "eksik"),
+            RunOutcome::Failed => write!(f, "hatalı"),
+        }
+    }
+}
+
+/// Çalıştırmanın bir aşamasına geçildiği an
+#[derive(Debug, Clone)]
+pub struct Phase {
+    pub name: String,
+    pub at: DateTime<FixedOffset>,
+}
+
+/// Bir denemede bir CRN için dönen sonuç kodu
+#[derive(Debug, Clone)]
+pub struct CrnCode {
+    pub crn: String,
+    pub action: CrnAction,
+    pub code: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttemptRecord {
+    pub number: u64,
+    /// Açılış denemesiyse açılışa göre planlanan ofset
+    pub burst_offset_ms: Option<i64>,
+    /// Gönderildiği sunucu saati
+    pub sent_at: DateTime<FixedOffset>,
+    pub latency: Duration,
+    /// `AttemptOutcome::kind`
+    pub outcome: String,
+    pub codes: Vec<CrnCode>,
+}
+
+impl AttemptRecord {
+    /// Açılışa göre ne kadar önce (-) ya da sonra (+) gönderildi
+    pub fn offset_ms(&self, opening: DateTime<FixedOffset>) -> i64 {
+        self.sent_at
+            .signed_duration_since(opening)
+            .num_milliseconds()
+    }
+
+    pub fn latency_ms(&self) -> f64 {
+        self.latency.as_secs_f64() * 1000.0
+    }
+}
+
+/// Tek bir pencerenin çalıştırılması; sonunda geçmiş veritabanına yazılır
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub id: Option<i64>,
+    pub username: String,
+    pub window: String,
+    pub config_hash: String,
+    pub opening: DateTime<FixedOffset>,
+    pub started_at: DateTime<FixedOffset>,
+    pub finished_at: DateTime<FixedOffset>,
+    pub phases: Vec<Phase>,
+    pub attempts: Vec<AttemptRecord>,
+    pub outcome: RunOutcome,
+    pub error: Option<String>,
+    /// Son durum kontrolüne göre her CRN'in işlemi gerçekleşti mi
+    pub crns: Vec<(String, CrnAction, bool)>,
+}
+
+impl RunRecord {
+    pub fn new(config: &Config, job: &Job, started_at: DateTime<FixedOffset>) -> Self {
+        Self {
+            id: None,
+            username: config.username.clone(),
+            window: job.label(),
+            config_hash: config_hash(config),
+            opening: job.time,
+            started_at,
+            finished_at: started_at,
+            phases: Vec::new(),
+            attempts: Vec::new(),
+            outcome: RunOutcome::Failed,
+            error: None,
+            crns: Vec::new(),
+        }
+    }
+
+    pub fn phase(&mut self, name: &str, at: DateTime<FixedOffset>) {
+        self.phases.push(Phase {
+            name: name.to_string(),
+            at,
+        });
+    }
+
+    pub fn attempt(
+        &mut self,
+        number: u64,
+        burst_offset_ms: Option<i64>,
+        sent_at: DateTime<FixedOffset>,
+        latency: Duration,
+        outcome: &AttemptOutcome,
+    ) {
+        let codes = outcome
+            .response()
+            .map(|body| {
+                let adds = body.add_results().into_iter().map(|r| (CrnAction::Add, r));
+                let drops = body
+                    .drop_results()
+                    .into_iter()
+                    .map(|r| (CrnAction::Drop, r));
+                adds.chain(drops)
+                    .map(|(action, (crn, code, _))| CrnCode { crn, action, code })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.attempts.push(AttemptRecord {
+            number,
+            burst_offset_ms,
+            sent_at,
+            latency,
+            outcome: outcome.kind().to_string(),
+            codes,
+        });
+    }
+
+    pub fn finish(
+        &mut self,
+        result: &Result<RegistrationReport, Box<dyn Error>>,
+        finished_at: DateTime<FixedOffset>,
+    ) {
+        self.finished_at = finished_at;
+        match result {
+            Ok(report) => {
+                self.outcome = if report.is_complete() {
+                    RunOutcome::Complete
+                } else {
+                    RunOutcome::Incomplete
+                };
+                let added = report
+                    .added()
+                    .iter()
+                    .map(|(crn, ok)| (crn.clone(), CrnAction::Add, *ok));
+                let dropped = report
+                    .dropped()
+                    .iter()
+                    .map(|(crn, ok)| (crn.clone(), CrnAction::Drop, *ok));
+                self.crns = added.chain(dropped).collect();
+            }
+            Err(e) => {
+                self.outcome = RunOutcome::Failed;
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+impl Display for RunRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(id) = self.id {
+            writeln!(f, "Çalıştırma #{}", id)?;
+        }
+        writeln!(f, "Kullanıcı: {}", self.username)?;
+        writeln!(f, "Pencere: {}", self.window)?;
+        writeln!(f, "Açılış: {}", self.opening)?;
+        writeln!(f, "Başlangıç: {}", self.started_at)?;
+        writeln!(f, "Bitiş: {}", self.finished_at)?;
+        writeln!(f, "Config özeti: {}", self.config_hash)?;
+        match &self.error {
+            Some(e) => writeln!(f, "Sonuç: {} ({})", self.outcome, e)?,
+            None => writeln!(f, "Sonuç: {}", self.outcome)?,
+        }
+
+        writeln!(f, "Aşamalar:")?;
+        for phase in &self.phases {
+            writeln!(f, "  {}  {}", phase.at, phase.name)?;
+        }
+
+        writeln!(f, "Denemeler:")?;
+        for attempt in &self.attempts {
+            write!(
+                f,
+                "  {}. {:+} ms, {:.1} ms sürdü, {}",
+                attempt.number,
+                attempt.offset_ms(self.opening),
+                attempt.latency_ms(),
+                attempt.outcome
+            )?;
+            if let Some(offset) = attempt.burst_offset_ms {
+                write!(f, " (açılış denemesi {:+} ms)", offset)?;
+            }
+            writeln!(f)?;
+            for code in &attempt.codes {
+                writeln!(f, "     CRN {}: {}", code.crn, code.code)?;
+            }
+        }
+
+        writeln!(f, "CRN'ler:")?;
+        for (crn, action, ok) in &self.crns {
+            let status = match (action, ok) {
+                (CrnAction::Add, true) => "alındı",
+                (CrnAction::Add, false) => "ALINAMADI",
+                (CrnAction::Drop, true) => "bırakıldı",
+                (CrnAction::Drop, false) => "BIRAKILAMADI",
+            };
+            writeln!(f, "  CRN {}: {}", crn, status)?;
+        }
+        Ok(())
+    }
+}
+
+/// `history` listesindeki bir satır
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub id: i64,
+    pub username: String,
+    pub window: String,
+    pub started_at: String,
+    pub outcome: RunOutcome,
+    pub attempts: u64,
+}
+
+impl Display for RunSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{}  {}  {}  {}  {} deneme, {}",
+            self.id, self.started_at, self.username, self.window, self.attempts, self.outcome
+        )
+    }
+}
+
+/// Parola dışındaki ayarların özeti; aynı config ile yapılan çalıştırmaları eşleştirmek için
+pub fn config_hash(config: &Config) -> String {
+    let mut config = config.clone();
+    config.password.clear();
+    let bytes = serde_json::to_vec(&config).unwrap_or_default();
+    format!("{:016x}", session::fnv1a(&bytes))
+}
+
+/// Tüm çalıştırmaların tutulduğu yerel SQLite veritabanı
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        let dir = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("itu-keplerbot");
+
+        Self {
+            path: dir.join("history.sqlite"),
+        }
+    }
+}
+
+impl History {
+    const SCHEMA: &str = "
+        CREATE TABLE IF NOT EXISTS runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL,
+            window TEXT NOT NULL,
+            config_hash TEXT NOT NULL,
+            opening TEXT NOT NULL,
+            started_at TEXT NOT NULL,
+            finished_at TEXT NOT NULL,
+            outcome TEXT NOT NULL,
+            error TEXT
+        );
+        CREATE TABLE IF NOT EXISTS phases (
+            run_id INTEGER NOT NULL REFERENCES runs(id),
+            name TEXT NOT NULL,
+            at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS attempts (
+            run_id INTEGER NOT NULL REFERENCES runs(id),
+            number INTEGER NOT NULL,
+            burst_offset_ms INTEGER,
+            sent_at TEXT NOT NULL,
+            latency_us INTEGER NOT NULL,
+            outcome TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS attempt_codes (
+            run_id INTEGER NOT NULL REFERENCES runs(id),
+            attempt INTEGER NOT NULL,
+            crn TEXT NOT NULL,
+            action TEXT NOT NULL,
+            code TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS crn_results (
+            run_id INTEGER NOT NULL REFERENCES runs(id),
+            crn TEXT NOT NULL,
+            action TEXT NOT NULL,
+            ok INTEGER NOT NULL
+        );
+    ";
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    fn open(&self) -> Result<Connection, Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let connection = Connection::open(&self.path)?;
+        // kullanıcı adları, hatalar ve deneme zamanları diğer kullanıcılara açık olmamalı
+        session::make_private(&self.path)?;
+        connection.execute_batch(Self::SCHEMA)?;
+        Ok(connection)
+    }
+
+    /// Çalıştırmayı yazar ve numarasını döner
+    pub fn record(&self, run: &RunRecord) -> Result<i64, Box<dyn Error>> {
+        let mut connection = self.open()?;
+        let tx = connection.transaction()?;
+
+        tx.execute(
+            "INSERT INTO runs (username, window, config_hash, opening, started_at, finished_at, outcome, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                run.username,
+                run.window,
+                run.config_hash,
+                run.opening.to_rfc3339(),
+                run.started_at.to_rfc3339(),
+                run.finished_at.to_rfc3339(),
+                run.outcome.name(),
+                run.error,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        for phase in &run.phases {
+            tx.execute(
+                "INSERT INTO phases (run_id, name, at) VALUES (?1, ?2, ?3)",
+                params![id, phase.name, phase.at.to_rfc3339()],
+            )?;
+        }
+        for attempt in &run.attempts {
+            tx.execute(
+                "INSERT INTO attempts (run_id, number, burst_offset_ms, sent_at, latency_us, outcome)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    id,
+                    attempt.number,
+                    attempt.burst_offset_ms,
+                    attempt.sent_at.to_rfc3339(),
+                    attempt.latency.as_micros() as i64,
+                    attempt.outcome,
+                ],
+            )?;
+            for code in &attempt.codes {
+                tx.execute(
+                    "INSERT INTO attempt_codes (run_id, attempt, crn, action, code)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![id, attempt.number, code.crn, code.action.name(), code.code],
+                )?;
+            }
+        }
+        for (crn, action, ok) in &run.crns {
+            tx.execute(
+                "INSERT INTO crn_results (run_id, crn, action, ok) VALUES (?1, ?2, ?3, ?4)",
+                params![id, crn, action.name(), ok],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// En yeniden eskiye çalıştırmalar
+    pub fn list(&self, limit: usize) -> Result<Vec<RunSummary>, Box<dyn Error>> {
+        let connection = self.open()?;
+        let mut statement = connection.prepare(
+            "SELECT id, username, window, started_at, outcome,
+                    (SELECT COUNT(*) FROM attempts WHERE attempts.run_id = runs.id)
+             FROM runs ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = statement.query_map(params![limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (id, username, window, started_at, outcome, attempts) = row?;
+            Ok(RunSummary {
+                id,
+                username,
+                window,
+                started_at,
+                outcome: parse_outcome(&outcome)?,
+                attempts: attempts as u64,
+            })
+        })
+        .collect()
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<RunRecord>, Box<dyn Error>> {
+        let connection = self.open()?;
+
+        let run = connection
+            .query_row(
+                "SELECT username, window, config_hash, opening, started_at, finished_at, outcome, error
+                 FROM runs WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((username, window, config_hash, opening, started_at, finished_at, outcome, error)) =
+            run
+        else {
+            return Ok(None);
+        };
+
+        let mut statement =
+            connection.prepare("SELECT name, at FROM phases WHERE run_id = ?1 ORDER BY rowid")?;
+        let phases = statement
+            .query_map(params![id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .map(|row| {
+                let (name, at) = row?;
+                Ok(Phase {
+                    name,
+                    at: parse_time(&at)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        let mut statement = connection.prepare(
+            "SELECT attempt, crn, action, code FROM attempt_codes WHERE run_id = ?1 ORDER BY rowid",
+        )?;
+        let codes = statement
+            .query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, u64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut statement = connection.prepare(
+            "SELECT number, burst_offset_ms, sent_at, latency_us, outcome
+             FROM attempts WHERE run_id = ?1 ORDER BY rowid",
+        )?;
+        let attempts = statement
+            .query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, u64>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?
+            .map(|row| {
+                let (number, burst_offset_ms, sent_at, latency_us, outcome) = row?;
+                let codes = codes
+                    .iter()
+                    .filter(|(attempt, ..)| *attempt == number)
+                    .map(|(_, crn, action, code)| {
+                        Ok(CrnCode {
+                            crn: crn.clone(),
+                            action: parse_action(action)?,
+                            code: code.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+                Ok(AttemptRecord {
+                    number,
+                    burst_offset_ms,
+                    sent_at: parse_time(&sent_at)?,
+                    latency: Duration::from_micros(latency_us.max(0) as u64),
+                    outcome,
+                    codes,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        let mut statement = connection
+            .prepare("SELECT crn, action, ok FROM crn_results WHERE run_id = ?1 ORDER BY rowid")?;
+        let crns = statement
+            .query_map(params![id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                ))
+            })?
+            .map(|row| {
+                let (crn, action, ok) = row?;
+                Ok((crn, parse_action(&action)?, ok))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(Some(RunRecord {
+            id: Some(id),
+            username,
+            window,
+            config_hash,
+            opening: parse_time(&opening)?,
+            started_at: parse_time(&started_at)?,
+            finished_at: parse_time(&finished_at)?,
+            phases,
+            attempts,
+            outcome: parse_outcome(&outcome)?,
+            error,
+            crns,
+        }))
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<FixedOffset>, Box<dyn Error>> {
+    Ok(DateTime::parse_from_rfc3339(value)?)
+}
+
+fn parse_outcome(value: &str) -> Result<RunOutcome, Box<dyn Error>> {
+    RunOutcome::from_name(value).ok_or_else(|| format!("bilinmeyen sonuç: {}", value).into())
+}
+
+fn parse_action(value: &str) -> Result<CrnAction, Box<dyn Error>> {
+    CrnAction::from_name(value).ok_or_else(|| format!("bilinmeyen işlem: {}", value).into())
+}
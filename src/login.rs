@@ -1,165 +1,154 @@
-use scraper::{Html, Selector};
-use serde::{Deserialize, Serialize};
-
-use crate::requester::Config;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct LoginFormHiddenFields {
-    #[serde(rename = "__EVENTTARGET")]
-    event_target: String,
-
-    #[serde(rename = "__EVENTARGUMENT")]
-    event_argument: String,
-
-    #[serde(rename = "__VIEWSTATE")]
-    viewstate: String,
-
-    #[serde(rename = "__VIEWSTATEGENERATOR")]
-    viewstate_generator: String,
-
-    #[serde(rename = "__EVENTVALIDATION")]
-    event_validation: String,
+use std::{error::Error, fmt::Display};
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+
+#[derive(Debug, Clone)]
+pub enum LoginFormError {
+    FormNotFound {
+        page_title: String,
+    },
+    MissingFields {
+        missing: Vec<&'static str>,
+        found: Vec<String>,
+    },
 }
 
-impl LoginFormHiddenFields {
-    #[allow(dead_code)]
-    const EVENT_TARGET_NAME: &str = "__EVENTTARGET";
-    #[allow(dead_code)]
-    const EVENT_ARGUMENT_NAME: &str = "__EVENTARGUMENT";
-    #[allow(dead_code)]
-    const VIEWSTATE_NAME: &str = "__VIEWSTATE";
-    #[allow(dead_code)]
-    const VIEWSTATE_GENERATOR_NAME: &str = "__VIEWSTATEGENERATOR";
-    #[allow(dead_code)]
-    const EVENT_VALIDATION_NAME: &str = "__EVENTVALIDATION";
-
-    pub fn new(
-        event_target: String,
-        event_argument: String,
-        viewstate: String,
-        viewstate_generator: String,
-        event_validation: String,
-    ) -> Self {
-        Self {
-            event_target,
-            event_argument,
-            viewstate,
-            viewstate_generator,
-            event_validation,
+impl Display for LoginFormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginFormError::FormNotFound { page_title } => write!(
+                f,
+                "Giriş sayfasında form bulunamadı (sayfa başlığı: \"{}\"). Giriş sayfası değişmiş olabilir.",
+                page_title
+            ),
+            LoginFormError::MissingFields { missing, found } => write!(
+                f,
+                "Giriş formunda beklenen alanlar yok: {}. Formda bulunan alanlar: {}. Giriş sayfası değişmiş olabilir.",
+                missing.join(", "),
+                found.join(", ")
+            ),
         }
     }
 }
 
-fn get_field_value_attr(document: &Html, selector: &Selector) -> String {
-    document
-        .select(&selector)
-        .next()
-        .and_then(|el| el.value().attr("value"))
-        .unwrap_or_default()
-        .into()
-}
+impl Error for LoginFormError {}
 
-impl From<Html> for LoginFormHiddenFields {
-    fn from(document: Html) -> Self {
-        let event_target_selector =
-            Selector::parse("input[name='__EVENTTARGET']").expect("__EVENTTARGET selector");
-        let event_target = get_field_value_attr(&document, &event_target_selector);
-
-        let event_argument_selector =
-            Selector::parse("input[name='__EVENTARGUMENT']").expect("__EVENTARGUMENT selector");
-        let event_argument = get_field_value_attr(&document, &event_argument_selector);
-
-        let viewstate_selector =
-            Selector::parse("input[name='__VIEWSTATE']").expect("__VIEWSTATE selector");
-        let viewstate = get_field_value_attr(&document, &viewstate_selector);
-
-        let viewstate_generator_selector = Selector::parse("input[name='__VIEWSTATEGENERATOR']")
-            .expect("__VIEWSTATEGENERATOR selector");
-        let viewstate_generator = get_field_value_attr(&document, &viewstate_generator_selector);
-
-        let event_validation_selector =
-            Selector::parse("input[name='__EVENTVALIDATION']").expect("__EVENTVALIDATION selector");
-        let event_validation = get_field_value_attr(&document, &event_validation_selector);
-
-        LoginFormHiddenFields::new(
-            event_target,
-            event_argument,
-            viewstate,
-            viewstate_generator,
-            event_validation,
-        )
-    }
+/// Giriş sayfasındaki ASP.NET WebForms formu. Sayfadaki tüm alanlar olduğu gibi geri gönderilir,
+/// sadece kullanıcı adı ve şifre değiştirilir.
+#[derive(Debug, Clone)]
+pub struct LoginForm {
+    action: Option<String>,
+    fields: Vec<(String, String)>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct LoginFormInputFields {
-    #[serde(rename = "ctl00$ContentPlaceHolder1$hfAppName")]
-    app_name: String,
-
-    #[serde(rename = "ctl00$ContentPlaceHolder1$hfToken")]
-    hf_token: String,
-
-    #[serde(rename = "ctl00$ContentPlaceHolder1$hfVerifier")]
-    hf_verifier: String,
-
-    #[serde(rename = "ctl00$ContentPlaceHolder1$hfCode")]
-    hf_code: String,
+impl LoginForm {
+    pub const USERNAME_FIELD: &str = "ctl00$ContentPlaceHolder1$tbUserName";
+    pub const PASSWORD_FIELD: &str = "ctl00$ContentPlaceHolder1$tbPassword";
+    pub const LOGIN_BUTTON_FIELD: &str = "ctl00$ContentPlaceHolder1$btnLogin";
+
+    /// Bunlardan biri eksikse sunucu girişi büyük ihtimalle reddeder
+    pub const REQUIRED_FIELDS: &[&str] = &[
+        "__VIEWSTATE",
+        "__VIEWSTATEGENERATOR",
+        "__EVENTVALIDATION",
+        Self::USERNAME_FIELD,
+        Self::PASSWORD_FIELD,
+        Self::LOGIN_BUTTON_FIELD,
+    ];
+
+    fn find_form(document: &Html) -> Option<ElementRef<'_>> {
+        let form_selector = Selector::parse("form").expect("form selector");
+        let username_selector = Selector::parse(&format!("input[name='{}']", Self::USERNAME_FIELD))
+            .expect("username selector");
+
+        let forms: Vec<ElementRef> = document.select(&form_selector).collect();
+        forms
+            .iter()
+            .find(|form| form.select(&username_selector).next().is_some())
+            .or(forms.first())
+            .copied()
+    }
 
-    #[serde(rename = "ctl00$ContentPlaceHolder1$hfState")]
-    hf_state: String,
+    fn page_title(document: &Html) -> String {
+        let title_selector = Selector::parse("title").expect("title selector");
+        document
+            .select(&title_selector)
+            .next()
+            .map(|t| t.text().collect::<String>().trim().to_string())
+            .unwrap_or_default()
+    }
 
-    #[serde(rename = "ctl00$ContentPlaceHolder1$tbUserName")]
-    username: String,
+    /// Tarayıcının göndereceği alanları toplar: işaretlenmemiş kutular ve giriş dışındaki butonlar gönderilmez
+    fn collect_fields(form: &ElementRef) -> Vec<(String, String)> {
+        let input_selector = Selector::parse("input[name]").expect("input selector");
+
+        form.select(&input_selector)
+            .filter_map(|input| {
+                let input = input.value();
+                let name = input.attr("name")?;
+                let input_type = input.attr("type").unwrap_or("text").to_lowercase();
+
+                let posted = match input_type.as_str() {
+                    "checkbox" | "radio" => input.attr("checked").is_some(),
+                    "submit" | "button" | "image" | "reset" => name == Self::LOGIN_BUTTON_FIELD,
+                    _ => true,
+                };
+
+                posted.then(|| {
+                    (
+                        name.to_string(),
+                        input.attr("value").unwrap_or("").to_string(),
+                    )
+                })
+            })
+            .collect()
+    }
 
-    #[serde(rename = "ctl00$ContentPlaceHolder1$tbPassword")]
-    password: String,
+    pub fn parse(document: &Html) -> Result<Self, LoginFormError> {
+        let form = Self::find_form(document).ok_or_else(|| LoginFormError::FormNotFound {
+            page_title: Self::page_title(document),
+        })?;
+
+        let fields = Self::collect_fields(&form);
+        let missing: Vec<&'static str> = Self::REQUIRED_FIELDS
+            .iter()
+            .filter(|required| !fields.iter().any(|(name, _)| name == *required))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(LoginFormError::MissingFields {
+                missing,
+                found: fields.into_iter().map(|(name, _)| name).collect(),
+            });
+        }
 
-    #[serde(rename = "ctl00$ContentPlaceHolder1$btnLogin")]
-    btn_login: String,
-}
+        Ok(Self {
+            action: form.value().attr("action").map(String::from),
+            fields,
+        })
+    }
 
-impl LoginFormInputFields {
-    const APP_NAME: &str = "İTÜ/Portal";
-    const HF_TOKEN: &str = "";
-    const HF_VERIFIER: &str = "";
-    const HF_CODE: &str = "";
-    const HF_STATE: &str = "";
-    const BTN_LOGIN: &str = "Giriş+/+Login";
-
-    pub fn new(username: String, password: String) -> Self {
-        Self {
-            app_name: Self::APP_NAME.into(),
-            hf_token: Self::HF_TOKEN.into(),
-            hf_verifier: Self::HF_VERIFIER.into(),
-            hf_code: Self::HF_CODE.into(),
-            hf_state: Self::HF_STATE.into(),
-            username,
-            password,
-            btn_login: Self::BTN_LOGIN.into(),
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        for (name, value) in self.fields.iter_mut() {
+            if name == Self::USERNAME_FIELD {
+                *value = username.to_string();
+            } else if name == Self::PASSWORD_FIELD {
+                *value = password.to_string();
+            }
         }
+        self
     }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct LoginFormBody {
-    #[serde(flatten)]
-    hidden_fields: LoginFormHiddenFields,
 
-    #[serde(flatten)]
-    input_fields: LoginFormInputFields,
-}
-
-impl LoginFormBody {
-    pub fn new(hidden_fields: LoginFormHiddenFields, input_fields: LoginFormInputFields) -> Self {
-        Self {
-            hidden_fields,
-            input_fields,
+    /// Formun gönderileceği adres, `action` yoksa sayfanın kendisi
+    pub fn action_url(&self, page_url: &Url) -> Result<Url, Box<dyn Error>> {
+        match &self.action {
+            Some(action) if !action.is_empty() => Ok(page_url.join(action)?),
+            _ => Ok(page_url.clone()),
         }
     }
-}
 
-impl From<Config> for LoginFormInputFields {
-    fn from(config: Config) -> Self {
-        Self::new(config.username, config.password)
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
     }
 }
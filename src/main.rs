@@ -1,30 +1,181 @@
+mod attempt;
+mod burst;
+mod catalog;
 mod cli;
+mod clock;
 mod course;
+mod doctor;
+mod history;
 mod login;
+mod policy;
+mod report;
 mod requester;
+mod schedule;
+mod session;
+mod state;
+mod time_input;
+mod warmup;
+mod wizard;
 
 use std::{
     error::Error,
     fs::File,
     io,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
 
+use catalog::Catalog;
 use clap::Parser;
-use cli::Cli;
-use requester::{Config, Requester};
-use serde_json;
+use cli::{
+    Cli, DoctorArgs, DropArgs, HistoryArgs, PlanArgs, RunArgs, SelectNowArgs, SessionArgs,
+    StatusArgs,
+};
+use clock::ServerClock;
+use futures::future::join_all;
+use requester::{Config, ConfigFile, Hedging, Requester};
+use schedule::Preferences;
+use session::ImportedSession;
 
 const DEFAULT_CONFIG_PATH: &str = "config.json";
 
-async fn run_requester(config_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+fn read_profiles(config_path: &Path) -> Result<Vec<Config>, Box<dyn Error>> {
     let config_file = File::open(config_path)?;
-    let config: Config = serde_json::from_reader(config_file)?;
-    let requester = Requester::new(config);
+    let config_file: ConfigFile = serde_json::from_reader(config_file)?;
+    Ok(config_file.into_profiles())
+}
+
+/// Tek hesapla çalışan komutlar için; birden fazla profil varsa hangisinin kastedildiği belli değil
+fn read_config(config_path: &Path) -> Result<Config, Box<dyn Error>> {
+    let mut profiles = read_profiles(config_path)?;
+    match profiles.len() {
+        1 => Ok(profiles.remove(0)),
+        0 => Err(format!("{} dosyasında profil yok", config_path.display()).into()),
+        n => Err(format!(
+            "{} dosyasında {} profil var, bu komut tek bir hesapla çalışır",
+            config_path.display(),
+            n
+        )
+        .into()),
+    }
+}
 
-    requester.run().await?;
+fn imported_session(session_args: &SessionArgs) -> Result<ImportedSession, Box<dyn Error>> {
+    let cookies = match &session_args.cookies_path {
+        Some(path) => Some(session::load_netscape_cookies(path)?),
+        None => None,
+    };
+    let jwt = match &session_args.jwt_path {
+        Some(path) => Some(session::read_jwt(path)?),
+        None => None,
+    };
 
-    Ok(())
+    Ok(ImportedSession { cookies, jwt })
+}
+
+fn build_requester(
+    config: Config,
+    session_args: &SessionArgs,
+) -> Result<Requester, Box<dyn Error>> {
+    let requester = Requester::new(config).with_session_reuse(session_args.reuse_session);
+    let imported = imported_session(session_args)?;
+
+    if imported.is_empty() {
+        Ok(requester)
+    } else {
+        Ok(requester.with_imported_session(imported))
+    }
+}
+
+async fn run_requester(run_args: RunArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let profiles = read_profiles(
+        &run_args
+            .config_path
+            .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH)),
+    )?;
+
+    println!("Sunucu saati ölçülüyor...");
+    let clock = match ServerClock::sync(&ServerClock::client()).await {
+        Ok(clock) => {
+            println!("Sunucu saati ile fark: {}", clock.offset());
+            clock
+        }
+        Err(e) => {
+            println!("Sunucu saati ölçülemedi, yerel saat kullanılacak: {}", e);
+            ServerClock::default()
+        }
+    };
+
+    let clock = Arc::new(Mutex::new(clock));
+
+    let session_args = &run_args.session_args;
+    if profiles.len() > 1
+        && (session_args.cookies_path.is_some() || session_args.jwt_path.is_some())
+    {
+        return Err("Tarayıcı oturumu birden fazla profille kullanılamaz".into());
+    }
+
+    let requesters = profiles
+        .into_iter()
+        .map(|config| {
+            Ok(build_requester(config, session_args)?
+                .with_clock(clock.clone())
+                .with_login_lead(run_args.login_lead)
+                .with_login_retries(run_args.login_retries)
+                .with_hedging(run_args.hedge_after.map(|after| Hedging {
+                    after,
+                    max_hedges: run_args.max_hedges,
+                }))
+                .with_burst(run_args.burst.clone())
+                .with_probe(run_args.probe)
+                .with_report_format(run_args.report_format))
+        })
+        .collect::<Result<Vec<Requester>, Box<dyn Error>>>()?;
+
+    requesters.iter().for_each(|r| r.print_plan());
+
+    if run_args.dry_run {
+        for requester in &requesters {
+            println!("===== İstekler: {} =====", requester.label());
+            requester.dry_run(run_args.with_login).await?;
+        }
+        println!("Deneme çalıştırması, ders seçimi gönderilmedi.");
+        return Ok(());
+    }
+
+    if run_args.confirm && !cli::confirm("Bu planla devam edilsin mi?")? {
+        return Err("Çalıştırma iptal edildi.".into());
+    }
+
+    let outcomes = join_all(requesters.iter().map(|r| r.run())).await;
+    if outcomes.iter().all(|outcomes| outcomes.is_empty()) {
+        return Err("Çalıştırılacak pencere yok, hepsinin zamanı geçmiş".into());
+    }
+
+    println!("===== Sonuçlar =====");
+    let mut failed = 0;
+    for (requester, outcomes) in requesters.iter().zip(outcomes) {
+        for outcome in outcomes {
+            println!("--- {} / {} ---", requester.label(), outcome.job.label());
+            match outcome.result {
+                Ok(report) => {
+                    print!("{}", report);
+                    if !report.is_complete() {
+                        failed += 1;
+                    }
+                }
+                Err(e) => {
+                    println!("Hata: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{} pencerenin işlemleri tamamlanamadı", n).into()),
+    }
 }
 
 fn write_config_to_file(file: &File, config: &Config) -> Result<(), Box<dyn Error>> {
@@ -53,6 +204,179 @@ fn write_config(output_path: &Path, config: &Config) -> Result<(), Box<dyn Error
     }
 }
 
+async fn plan_schedule(plan_args: PlanArgs) -> Result<(), Box<dyn Error>> {
+    let config_path = plan_args
+        .config_path
+        .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH));
+    let mut config = read_config(&config_path)?;
+
+    let catalog = match &plan_args.catalog_path {
+        Some(catalog_path) => Catalog::from_file(catalog_path)?,
+        None => {
+            println!("Ders programı OBS'den çekiliyor...");
+            Catalog::fetch(&reqwest::Client::new(), &plan_args.course_codes).await?
+        }
+    };
+
+    let preferences = Preferences {
+        not_before: plan_args.not_before,
+        free_days: plan_args.free_days,
+        preferred_instructors: plan_args.preferred_instructors,
+    };
+
+    let plan = schedule::plan(
+        &catalog,
+        &plan_args.course_codes,
+        &preferences,
+        plan_args.alternative_count + 1,
+    )?;
+    if plan.truncated {
+        println!(
+            "Kombinasyon sayısı çok fazla, arama yarıda kesildi. Daha iyi programlar denenmemiş olabilir; ders ya da tercih sayısını azaltmayı deneyin."
+        );
+    }
+    let Some((chosen, alternatives)) = plan.schedules.split_first() else {
+        return Err("Çakışmasız bir program bulunamadı".into());
+    };
+
+    if plan.truncated {
+        println!("En az {} çakışmasız program bulundu.", plan.found);
+    } else {
+        println!("{} çakışmasız program bulundu.", plan.found);
+    }
+    println!("Seçilen program:\n{}", chosen);
+    alternatives
+        .iter()
+        .enumerate()
+        .for_each(|(i, s)| println!("{}. alternatif:\n{}", i + 1, s));
+
+    config.crn_list = chosen.crn_list();
+    config.alternatives = alternatives.iter().map(|s| s.crn_list()).collect();
+    write_config(&config_path, &config)
+}
+
+async fn show_status(status_args: StatusArgs) -> Result<(), Box<dyn Error>> {
+    let config = read_config(
+        &status_args
+            .config_path
+            .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH)),
+    )?;
+    let requester = build_requester(config, &status_args.session_args)?;
+
+    let registered = requester.status().await?;
+    print!("{}", registered);
+
+    Ok(())
+}
+
+async fn add_now(args: SelectNowArgs) -> Result<(), Box<dyn Error>> {
+    let mut config = read_config(
+        &args
+            .config_path
+            .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH)),
+    )?;
+    config.crn_list = args.crn_list;
+    config.scrn_list = Vec::new();
+
+    build_requester(config, &args.session_args)?
+        .select_now(args.try_count)
+        .await
+}
+
+async fn drop_now(args: DropArgs) -> Result<(), Box<dyn Error>> {
+    let select_args = args.select_now_args;
+    if !args.yes {
+        let prompt = format!(
+            "{} CRN'li dersler bırakılacak. Emin misin?",
+            select_args.crn_list.join(", ")
+        );
+        if !cli::confirm(&prompt)? {
+            return Err("Bırakma iptal edildi.".into());
+        }
+    }
+
+    let mut config = read_config(
+        &select_args
+            .config_path
+            .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH)),
+    )?;
+    config.crn_list = Vec::new();
+    config.scrn_list = select_args.crn_list;
+
+    build_requester(config, &select_args.session_args)?
+        .select_now(select_args.try_count)
+        .await
+}
+
+async fn run_doctor(doctor_args: DoctorArgs) -> Result<(), Box<dyn Error>> {
+    let config_path = doctor_args
+        .config_path
+        .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH));
+    let requester = match read_config(&config_path) {
+        Ok(config) => Some(Requester::new(config)),
+        Err(e) => {
+            println!(
+                "{} okunamadı, giriş gerektiren kontroller atlanacak: {}",
+                config_path.display(),
+                e
+            );
+            None
+        }
+    };
+
+    let checks = doctor::run(requester.as_ref(), doctor_args.send_selection).await;
+    checks.iter().for_each(|check| print!("{}", check));
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == doctor::CheckStatus::Fail)
+        .count();
+    match failed {
+        0 => Ok(()),
+        n => Err(format!("{} kontrol başarısız", n).into()),
+    }
+}
+
+fn show_history(history_args: HistoryArgs) -> Result<(), Box<dyn Error>> {
+    let history = history::History::default();
+
+    let Some(run_id) = history_args.run_id else {
+        let runs = history.list(history_args.limit)?;
+        if runs.is_empty() {
+            println!("Henüz kaydedilmiş bir çalıştırma yok.");
+        }
+        runs.iter().for_each(|run| println!("{}", run));
+        return Ok(());
+    };
+
+    let Some(run) = history.get(run_id)? else {
+        return Err(format!("#{} numaralı çalıştırma bulunamadı", run_id).into());
+    };
+
+    match history_args.report_format {
+        Some(format) => {
+            // çalıştırma sırasında yazılan raporla aynı şekilde parola da silinir
+            let config_path = history_args
+                .config_path
+                .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH));
+            let passwords: Vec<String> = read_profiles(&config_path)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|config| config.username == run.username)
+                .map(|config| config.password)
+                .collect();
+            let passwords: Vec<&str> = passwords.iter().map(String::as_str).collect();
+
+            let path = report::Report::new(&run)
+                .redacting(&passwords)
+                .write(&format!("run-{}", run_id), format)?;
+            println!("Rapor {} dosyasına yazıldı.", path.display());
+        }
+        None => print!("{}", run),
+    }
+    Ok(())
+}
+
 #[allow(dead_code)]
 fn read_config_file(config_path: &Path) -> Result<(), Box<dyn Error>> {
     let file = File::open(config_path)?;
@@ -72,24 +396,48 @@ async fn main() {
                 .output_path
                 .clone()
                 .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH));
-            match write_config(&output_path, &make_config_args.clone().into()) {
+            let config = if make_config_args.interactive {
+                wizard::run(make_config_args).await
+            } else {
+                Ok(make_config_args.into())
+            };
+            match config.and_then(|config| {
+                println!("Ders seçim zamanı: {}", time_input::describe(&config.time));
+                write_config(&output_path, &config)
+            }) {
                 Ok(_) => (),
                 Err(e) => eprintln!("{}", e),
             }
         }
-        cli::Command::Run(run_args) => {
-            match run_requester(
-                &run_args
-                    .config_path
-                    .unwrap_or(PathBuf::from(DEFAULT_CONFIG_PATH)),
-            )
-            .await
-            {
-                Ok(_) => println!(
-                    "Program başarıyla sonlandı. Umarım derslerini alabilmişsindir! <3 :pray:"
-                ),
-                Err(e) => eprintln!("Özür dilerim ama program hatalı sonlandı: {}", e),
+        cli::Command::Run(run_args) => match run_requester(run_args).await {
+            Ok(_) => {
+                println!("Program başarıyla sonlandı. Umarım derslerini alabilmişsindir! <3 :pray:")
             }
-        }
+            Err(e) => eprintln!("Özür dilerim ama program hatalı sonlandı: {}", e),
+        },
+        cli::Command::Plan(plan_args) => match plan_schedule(plan_args).await {
+            Ok(_) => (),
+            Err(e) => eprintln!("{}", e),
+        },
+        cli::Command::Status(status_args) => match show_status(status_args).await {
+            Ok(_) => (),
+            Err(e) => eprintln!("{}", e),
+        },
+        cli::Command::Add(args) => match add_now(args).await {
+            Ok(_) => println!("Ekleme tamamlandı."),
+            Err(e) => eprintln!("{}", e),
+        },
+        cli::Command::Drop(args) => match drop_now(args).await {
+            Ok(_) => println!("Bırakma tamamlandı."),
+            Err(e) => eprintln!("{}", e),
+        },
+        cli::Command::Doctor(args) => match run_doctor(args).await {
+            Ok(_) => println!("Tüm kontroller başarılı."),
+            Err(e) => eprintln!("{}", e),
+        },
+        cli::Command::History(args) => match show_history(args) {
+            Ok(_) => (),
+            Err(e) => eprintln!("{}", e),
+        },
     }
 }
@@ -0,0 +1,140 @@
+use std::{collections::BTreeMap, fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::attempt::AttemptOutcome;
+
+/// Bir denemenin sonucuna göre yapılacak iş
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    /// Normal aralıkla devam et
+    Continue,
+    /// Art arda her tekrarda bekleme ikiye katlanır, `max_ms` ile sınırlı ve rastgele dağıtılmış
+    Backoff { base_ms: u64, max_ms: u64 },
+    /// Belirli bir süre bekle
+    Pause { seconds: u64 },
+    /// Yeniden giriş yapıp yeni token ile devam et
+    Relogin,
+    /// Denemeleri bırak
+    Abort,
+}
+
+impl Action {
+    /// Birden fazla kural eşleşirse en ağırı uygulanır
+    fn severity(&self) -> u8 {
+        match self {
+            Action::Continue => 0,
+            Action::Backoff { .. } => 1,
+            Action::Pause { .. } => 2,
+            Action::Relogin => 3,
+            Action::Abort => 4,
+        }
+    }
+
+    /// Sonraki denemeden önce beklenecek süre, `streak` bu kuralın art arda kaçıncı kez uygulandığı
+    pub fn delay(&self, normal: Duration, streak: u32) -> Duration {
+        match self {
+            Action::Continue | Action::Relogin | Action::Abort => normal,
+            Action::Backoff { base_ms, max_ms } => {
+                let ceiling = base_ms
+                    .saturating_mul(1 << streak.min(16))
+                    .min(*max_ms)
+                    .max(*base_ms);
+                // sunucuya aynı anda yüklenen herkesle aynı adımda kalmamak için
+                let jittered = fastrand::u64(*base_ms..=ceiling);
+                normal.max(Duration::from_millis(jittered))
+            }
+            Action::Pause { seconds } => normal.max(Duration::from_secs(*seconds)),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Continue => write!(f, "devam"),
+            Action::Backoff { base_ms, max_ms } => {
+                write!(f, "artan bekleme ({}-{} ms)", base_ms, max_ms)
+            }
+            Action::Pause { seconds } => write!(f, "{} saniye ara", seconds),
+            Action::Relogin => write!(f, "yeniden giriş"),
+            Action::Abort => write!(f, "dur"),
+        }
+    }
+}
+
+/// Sonuç kodlarını (ör. "VAL13"), HTTP durum kodlarını (ör. "429") ve cevap türlerini
+/// (ör. "network", "maintenance") işlemlere eşler. Config'teki kurallar varsayılanların üzerine yazılır.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Policy {
+    rules: BTreeMap<String, Action>,
+}
+
+impl Policy {
+    fn defaults() -> BTreeMap<String, Action> {
+        let backoff = |base_ms, max_ms| Action::Backoff { base_ms, max_ms };
+
+        BTreeMap::from([
+            // TemporarilyBlocked: üstüne gitmek engeli uzatabilir
+            ("VAL13".to_string(), backoff(6_000, 60_000)),
+            // SystemNoAnswer
+            ("VAL14".to_string(), backoff(3_000, 30_000)),
+            // ProcessOngoing: önceki istek hâlâ işleniyor
+            ("VAL16".to_string(), backoff(3_000, 15_000)),
+            // Blocked: sadece o CRN'e dair, diğer bekleyen CRN'ler için denemeye devam edilir
+            ("VAL18".to_string(), Action::Continue),
+            ("ERRLoad".to_string(), backoff(3_000, 30_000)),
+            ("401".to_string(), Action::Relogin),
+            ("403".to_string(), Action::Relogin),
+            ("429".to_string(), backoff(5_000, 60_000)),
+            ("502".to_string(), backoff(3_000, 10_000)),
+            ("503".to_string(), backoff(3_000, 10_000)),
+            ("504".to_string(), backoff(3_000, 10_000)),
+            ("maintenance".to_string(), Action::Pause { seconds: 30 }),
+        ])
+    }
+
+    /// Varsayılan kurallar ve üzerine config'teki kurallar
+    pub fn effective(&self) -> BTreeMap<String, Action> {
+        let mut rules = Self::defaults();
+        rules.extend(self.rules.clone());
+        rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Denemenin eşleşen kuralları arasından en ağır olanı, kuralın adıyla birlikte
+    pub fn decide(&self, outcome: &AttemptOutcome) -> (String, Action) {
+        let rules = self.effective();
+
+        outcome
+            .policy_keys()
+            .into_iter()
+            .filter_map(|key| rules.get(&key).map(|action| (key, *action)))
+            .max_by_key(|(_, action)| action.severity())
+            .unwrap_or_else(|| (outcome.kind().to_string(), Action::Continue))
+    }
+}
+
+/// Aynı kurala art arda kaç kez takıldığını tutar, artan bekleme bunu kullanır
+#[derive(Debug, Default)]
+pub struct PolicyTracker {
+    last_rule: Option<String>,
+    streak: u32,
+}
+
+impl PolicyTracker {
+    pub fn record(&mut self, rule: &str) -> u32 {
+        if self.last_rule.as_deref() == Some(rule) {
+            self.streak += 1;
+        } else {
+            self.last_rule = Some(rule.to_string());
+            self.streak = 0;
+        }
+        self.streak
+    }
+}
@@ -0,0 +1,448 @@
+use std::{error::Error, path::PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{course, history::RunRecord, session, state::CrnAction};
+
+/// Raporun yazılacağı biçim
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn parse(arg: &str) -> Result<Self, String> {
+        match arg.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("{} bir rapor biçimi değil (markdown, html)", arg)),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+enum Block {
+    Heading(String),
+    Fields(Vec<(&'static str, String)>),
+    Table {
+        headers: &'static [&'static str],
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// Bir çalıştırmanın paylaşılabilir raporu. Kullanıcı alanındaki ad kısaltılır; hata ve dosya yolu
+/// gibi metinlerde geçen kullanıcı adı, verilen gizli değerler (parola gibi) ve API Token'lar silinir.
+pub struct Report {
+    title: String,
+    blocks: Vec<Block>,
+    secrets: Vec<String>,
+}
+
+impl Report {
+    pub fn new(run: &RunRecord) -> Self {
+        let mut report = Self {
+            title: format!("Ders Seçimi Raporu: {}", run.window),
+            blocks: Vec::new(),
+            secrets: vec![run.username.clone()],
+        };
+
+        let mut summary = vec![
+            ("Kullanıcı", mask(&run.username)),
+            ("Açılış", time(run.opening)),
+            ("Başlangıç", time(run.started_at)),
+            ("Bitiş", time(run.finished_at)),
+            ("Config özeti", run.config_hash.clone()),
+            ("Sonuç", run.outcome.to_string()),
+        ];
+        if let Some(id) = run.id {
+            summary.insert(0, ("Çalıştırma", format!("#{}", id)));
+        }
+        if let Some(error) = &run.error {
+            summary.push(("Hata", error.clone()));
+        }
+        report.blocks.push(Block::Fields(summary));
+
+        report
+            .blocks
+            .push(Block::Heading("Zaman Çizelgesi".to_string()));
+        report.blocks.push(Block::Table {
+            headers: &["Zaman", "Açılışa göre", "Aşama"],
+            rows: run
+                .phases
+                .iter()
+                .map(|phase| {
+                    vec![
+                        time(phase.at),
+                        offset(phase.at, run.opening),
+                        phase.name.clone(),
+                    ]
+                })
+                .collect(),
+        });
+
+        report.blocks.push(Block::Heading("Denemeler".to_string()));
+        report.blocks.push(Block::Table {
+            headers: &["#", "Gönderim", "Açılışa göre", "Süre", "Sonuç"],
+            rows: run
+                .attempts
+                .iter()
+                .map(|attempt| {
+                    let outcome = match attempt.burst_offset_ms {
+                        Some(burst) => {
+                            format!("{} (açılış denemesi {:+} ms)", attempt.outcome, burst)
+                        }
+                        None => attempt.outcome.clone(),
+                    };
+                    vec![
+                        attempt.number.to_string(),
+                        time(attempt.sent_at),
+                        offset(attempt.sent_at, run.opening),
+                        format!("{:.1} ms", attempt.latency_ms()),
+                        outcome,
+                    ]
+                })
+                .collect(),
+        });
+
+        report
+            .blocks
+            .push(Block::Heading("CRN Sonuçları".to_string()));
+        report.blocks.push(Block::Table {
+            headers: &["CRN", "İşlem", "Son durum"],
+            rows: run
+                .crns
+                .iter()
+                .map(|(crn, action, ok)| {
+                    let status = match (action, ok) {
+                        (CrnAction::Add, true) => "alındı",
+                        (CrnAction::Add, false) => "ALINAMADI",
+                        (CrnAction::Drop, true) => "bırakıldı",
+                        (CrnAction::Drop, false) => "BIRAKILAMADI",
+                    };
+                    vec![crn.clone(), action_name(*action), status.to_string()]
+                })
+                .collect(),
+        });
+
+        for (crn, action) in crns(run) {
+            report.blocks.push(Block::Heading(format!(
+                "CRN {} ({})",
+                crn,
+                action_name(action)
+            )));
+            report.blocks.push(Block::Table {
+                headers: &["Deneme", "Açılışa göre", "Kod", "Açıklama"],
+                rows: transitions(run, &crn, action),
+            });
+        }
+
+        report
+    }
+
+    /// Rapordan silinecek değerler, ör. parola
+    pub fn redacting(mut self, secrets: &[&str]) -> Self {
+        self.secrets
+            .extend(secrets.iter().map(|secret| secret.to_string()));
+        self
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.markdown(),
+            ReportFormat::Html => self.html(),
+        }
+    }
+
+    /// Raporu rapor klasörüne yazar ve dosyanın yolunu döner
+    pub fn write(&self, name: &str, format: ReportFormat) -> Result<PathBuf, Box<dyn Error>> {
+        let dir = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("itu-keplerbot")
+            .join("reports");
+        let path = dir.join(format!("{}.{}", name, format.extension()));
+        session::write_private_file(&path, self.render(format).as_bytes())?;
+        Ok(path)
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let text = self
+            .secrets
+            .iter()
+            .filter(|secret| !secret.is_empty())
+            .fold(text.to_string(), |text, secret| redact_token(&text, secret));
+
+        // API Token'lar base64 JSON olduğu için "eyJ" ile başlar
+        text.split(' ')
+            .map(|word| {
+                if word.starts_with("eyJ") && word.len() > 20 {
+                    "[token]"
+                } else {
+                    word
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn markdown(&self) -> String {
+        let cell = |text: &str| self.redact(text).replace('|', "\\|");
+        let mut out = format!("# {}\n", cell(&self.title));
+
+        for block in &self.blocks {
+            out.push('\n');
+            match block {
+                Block::Heading(text) => out.push_str(&format!("## {}\n", cell(text))),
+                Block::Fields(fields) => fields.iter().for_each(|(name, value)| {
+                    out.push_str(&format!("- **{}:** {}\n", name, cell(value)))
+                }),
+                Block::Table { rows, .. } if rows.is_empty() => out.push_str("_Kayıt yok._\n"),
+                Block::Table { headers, rows } => {
+                    out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                    out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+                    for row in rows {
+                        let row: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                        out.push_str(&format!("| {} |\n", row.join(" | ")));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let cell = |text: &str| escape_html(&self.redact(text));
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"tr\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}\
+             td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}</style>\n\
+             </head>\n<body>\n<h1>{}</h1>\n",
+            cell(&self.title),
+            cell(&self.title)
+        );
+
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => out.push_str(&format!("<h2>{}</h2>\n", cell(text))),
+                Block::Fields(fields) => {
+                    out.push_str("<ul>\n");
+                    fields.iter().for_each(|(name, value)| {
+                        out.push_str(&format!("<li><b>{}:</b> {}</li>\n", name, cell(value)))
+                    });
+                    out.push_str("</ul>\n");
+                }
+                Block::Table { rows, .. } if rows.is_empty() => {
+                    out.push_str("<p><i>Kayıt yok.</i></p>\n")
+                }
+                Block::Table { headers, rows } => {
+                    out.push_str("<table>\n<tr>");
+                    headers
+                        .iter()
+                        .for_each(|h| out.push_str(&format!("<th>{}</th>", h)));
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        row.iter()
+                            .for_each(|c| out.push_str(&format!("<td>{}</td>", cell(c))));
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+/// Raporda görünen CRN'ler: son durumu bilinenler, yoksa denemelerde geçenler
+fn crns(run: &RunRecord) -> Vec<(String, CrnAction)> {
+    let mut crns: Vec<(String, CrnAction)> = run
+        .crns
+        .iter()
+        .map(|(crn, action, _)| (crn.clone(), *action))
+        .collect();
+    for code in run.attempts.iter().flat_map(|a| &a.codes) {
+        if !crns
+            .iter()
+            .any(|(c, a)| *c == code.crn && *a == code.action)
+        {
+            crns.push((code.crn.clone(), code.action));
+        }
+    }
+    crns
+}
+
+/// Sadece kodun değiştiği denemeler, aynı kodun tekrarları atlanır
+fn transitions(run: &RunRecord, crn: &str, action: CrnAction) -> Vec<Vec<String>> {
+    let mut last: Option<&str> = None;
+    let mut rows = Vec::new();
+
+    for attempt in &run.attempts {
+        let Some(code) = attempt
+            .codes
+            .iter()
+            .find(|c| c.crn == crn && c.action == action)
+        else {
+            continue;
+        };
+        if last == Some(code.code.as_str()) {
+            continue;
+        }
+
+        rows.push(vec![
+            attempt.number.to_string(),
+            offset(attempt.sent_at, run.opening),
+            code.code.clone(),
+            course::describe_code(&code.code)
+                .unwrap_or("Bilinmeyen sonuç kodu")
+                .to_string(),
+        ]);
+        last = Some(&code.code);
+    }
+    rows
+}
+
+fn action_name(action: CrnAction) -> String {
+    match action {
+        CrnAction::Add => "ekleme".to_string(),
+        CrnAction::Drop => "bırakma".to_string(),
+    }
+}
+
+fn time(at: DateTime<FixedOffset>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S%.3f %:z").to_string()
+}
+
+fn offset(at: DateTime<FixedOffset>, opening: DateTime<FixedOffset>) -> String {
+    format!(
+        "{:+} ms",
+        at.signed_duration_since(opening).num_milliseconds()
+    )
+}
+
+/// Gizli değer sadece tek başına geçtiği yerlerde silinir, "1234" gibi kısa bir parola CRN'leri,
+/// saatleri ya da süreleri bozmaz
+fn redact_token(text: &str, secret: &str) -> String {
+    let mut out = String::new();
+    let mut from = 0;
+    for (start, _) in text.match_indices(secret) {
+        let end = start + secret.len();
+        if start >= from && is_separate(text, start, end) {
+            out.push_str(&text[from..start]);
+            out.push_str("***");
+            from = end;
+        }
+    }
+    out.push_str(&text[from..]);
+    out
+}
+
+/// Önündeki ve arkasındaki karakter harf, rakam ya da "_" değilse değer ayrı bir kelimedir. Rakamla
+/// biten bir değerin arkasında "." ve ardından rakam varsa (ör. "1234.5") sayının devamı sayılır.
+fn is_separate(text: &str, start: usize, end: usize) -> bool {
+    let separate = |neighbor: Option<char>, beyond: Option<char>, edge: Option<char>| match neighbor
+    {
+        None => true,
+        Some(c) if c.is_alphanumeric() || c == '_' => false,
+        Some('.' | ',' | ':') => {
+            !(edge.is_some_and(|c| c.is_ascii_digit())
+                && beyond.is_some_and(|c| c.is_ascii_digit()))
+        }
+        Some(_) => true,
+    };
+
+    let secret = &text[start..end];
+    let mut before = text[..start].chars().rev();
+    let mut after = text[end..].chars();
+    separate(before.next(), before.next(), secret.chars().next())
+        && separate(after.next(), after.next(), secret.chars().next_back())
+}
+
+/// En fazla ilk iki karakter görünür, kısa adlarda daha azı
+fn mask(secret: &str) -> String {
+    let visible = (secret.chars().count() / 3).min(2);
+    let visible: String = secret.chars().take(visible).collect();
+    format!("{}***", visible)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(secrets: &[&str], fields: Vec<(&'static str, String)>) -> Report {
+        Report {
+            title: "Güz".to_string(),
+            blocks: vec![Block::Fields(fields)],
+            secrets: secrets.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn short_secrets_do_not_touch_numbers() {
+        let report = report(
+            &["1234"],
+            vec![
+                ("CRN", "21234".to_string()),
+                ("Süre", "1234.5 ms".to_string()),
+                ("Zaman", "2025-02-10 12:34:56.123 +03:00".to_string()),
+                ("Hata", "parola 1234 kabul edilmedi".to_string()),
+            ],
+        );
+        let markdown = report.render(ReportFormat::Markdown);
+
+        assert!(markdown.contains("21234"), "{}", markdown);
+        assert!(markdown.contains("1234.5 ms"), "{}", markdown);
+        assert!(markdown.contains("12:34:56.123"), "{}", markdown);
+        assert!(markdown.contains("parola *** kabul"), "{}", markdown);
+    }
+
+    #[test]
+    fn username_is_removed_from_free_text() {
+        let report = report(
+            &["yilmaza20"],
+            vec![
+                ("Kullanıcı", mask("yilmaza20")),
+                (
+                    "Hata",
+                    "/home/x/.cache/itu-keplerbot/yilmaza20.json okunamadı".to_string(),
+                ),
+                ("Aşama", "yilmaza20 için giriş".to_string()),
+                ("Başka", "yilmaza201".to_string()),
+            ],
+        );
+        let html = report.render(ReportFormat::Html);
+
+        assert!(html.contains("<b>Kullanıcı:</b> yi***"), "{}", html);
+        assert!(html.contains("itu-keplerbot/***.json"), "{}", html);
+        assert!(html.contains("*** için giriş"), "{}", html);
+        assert!(html.contains("yilmaza201"), "{}", html);
+    }
+
+    #[test]
+    fn tokens_are_removed() {
+        let report = report(
+            &[],
+            vec![(
+                "Hata",
+                "token eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.x geçersiz".to_string(),
+            )],
+        );
+        assert!(report
+            .render(ReportFormat::Markdown)
+            .contains("token [token] geçersiz"));
+    }
+}
@@ -1,27 +1,168 @@
-use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
-use reqwest::{Client, Request, RequestBuilder, Response};
+use chrono::{FixedOffset, TimeDelta, Utc};
+use futures::{
+    future::{join_all, select_ok},
+    stream::{FuturesUnordered, StreamExt},
+};
+use reqwest::{Client, ClientBuilder, Request, RequestBuilder, Response};
+use reqwest_cookie_store::CookieStoreMutex;
 use scraper::Html;
 use serde::{Deserialize, Serialize};
-use std::{error::Error, thread};
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
 
 use crate::{
+    attempt::{self, AttemptOutcome},
+    burst::{BurstLog, BurstRecord},
     cli::MakeConfigArgs,
-    course::{CourseSelectionRequestBody, CourseSelectionResponseBody},
-    login::{LoginFormBody, LoginFormHiddenFields, LoginFormInputFields},
+    clock::{self, ServerClock},
+    course::{CourseSelectionRequestBody, RegisteredCoursesResponseBody, RegistrationReport},
+    history::{History, RunRecord},
+    login::LoginForm,
+    policy::{Action, Policy, PolicyTracker},
+    report::{Report, ReportFormat},
+    session::{self, CachedSession, ImportedSession, SessionCache},
+    state::{RunState, StateJournal},
+    warmup::PinnedResolver,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Birden fazla profil çalıştırılırken raporda görünen ad, verilmezse kullanıcı adı kullanılır
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
     pub username: String,
     pub password: String,
     pub time: chrono::DateTime<FixedOffset>,
     pub crn_list: Vec<String>,
     pub scrn_list: Vec<String>,
+
+    /// `plan` ile bulunan, seçilen programa alternatif CRN listeleri
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<Vec<String>>,
+
+    /// Ana ders seçiminden sonraki pencereler (ekle-bırak, ikinci öncelik...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<Job>,
+
+    #[serde(default, skip_serializing_if = "Timeouts::is_default")]
+    pub timeouts: Timeouts,
+
+    /// Sonuç kodlarına ve HTTP durumlarına göre ne yapılacağı, varsayılanların üzerine yazılır
+    #[serde(default, skip_serializing_if = "Policy::is_empty")]
+    pub policy: Policy,
+}
+
+/// Bir aşamadaki isteklerin bağlantı kurma ve toplam süre sınırları, milisaniye cinsinden
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct PhaseTimeout {
+    pub connect_ms: u64,
+    pub total_ms: u64,
+}
+
+impl PhaseTimeout {
+    const fn new(connect_ms: u64, total_ms: u64) -> Self {
+        Self {
+            connect_ms,
+            total_ms,
+        }
+    }
+
+    fn builder(
+        &self,
+        cookies: Arc<CookieStoreMutex>,
+        resolver: Arc<PinnedResolver>,
+    ) -> ClientBuilder {
+        Client::builder()
+            .cookie_provider(cookies)
+            .dns_resolver(resolver)
+            .connect_timeout(Duration::from_millis(self.connect_ms))
+            .timeout(Duration::from_millis(self.total_ms))
+    }
+
+    fn client(&self, cookies: Arc<CookieStoreMutex>, resolver: Arc<PinnedResolver>) -> Client {
+        self.builder(cookies, resolver)
+            .build()
+            .expect("Client::builder()")
+    }
+}
+
+/// Açılış anında takılan tek bir bağlantı tüm denemeleri bekletmesin diye her aşamanın kendi süre sınırı var
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct Timeouts {
+    pub login: PhaseTimeout,
+    pub jwt: PhaseTimeout,
+    pub selection: PhaseTimeout,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            login: PhaseTimeout::new(5_000, 20_000),
+            jwt: PhaseTimeout::new(5_000, 10_000),
+            selection: PhaseTimeout::new(2_000, 5_000),
+        }
+    }
+}
+
+impl Timeouts {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Cevap gecikirse aynı isteğin yeni bir bağlantıdan tekrar gönderilmesi
+#[derive(Debug, Clone, Copy)]
+pub struct Hedging {
+    /// İlk istek bu kadar sürede cevap vermezse ikincisi gönderilir
+    pub after: Duration,
+    /// Bir pencerede toplam en fazla kaç ek istek gönderilebilir
+    pub max_hedges: u32,
+}
+
+/// Tek bir ders seçim penceresi
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Job {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    pub time: chrono::DateTime<FixedOffset>,
+
+    #[serde(default)]
+    pub crn_list: Vec<String>,
+
+    #[serde(default)]
+    pub scrn_list: Vec<String>,
+}
+
+impl Job {
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self.time.to_string(),
+        }
+    }
+}
+
+/// Bir pencerenin sonucu, bir pencerenin hata vermesi sonrakileri engellemez
+#[derive(Debug)]
+pub struct JobOutcome {
+    pub job: Job,
+    pub result: Result<RegistrationReport, Box<dyn Error>>,
 }
 
 impl Config {
     const TRT_OFFSET_SECONDS: i64 = 3 * 3600; // UTC+3 TRT
-    const TRT_TIMEZONE: FixedOffset = FixedOffset::east_opt(Self::TRT_OFFSET_SECONDS as i32)
+    pub const TRT_TIMEZONE: FixedOffset = FixedOffset::east_opt(Self::TRT_OFFSET_SECONDS as i32)
         .expect("TRT Timezone should not fail");
     pub fn new(
         username: String,
@@ -31,180 +172,1463 @@ impl Config {
         scrn_list: Vec<String>,
     ) -> Self {
         Self {
+            name: None,
             username,
             password,
             time,
             crn_list,
             scrn_list,
+            alternatives: Vec::new(),
+            jobs: Vec::new(),
+            timeouts: Timeouts::default(),
+            policy: Policy::default(),
+        }
+    }
+
+    /// Üst seviyedeki `time`, `crn_list` ve `scrn_list` alanlarından oluşan ana pencere
+    pub fn main_job(&self) -> Job {
+        Job {
+            name: None,
+            time: self.time,
+            crn_list: self.crn_list.clone(),
+            scrn_list: self.scrn_list.clone(),
+        }
+    }
+
+    /// Ana pencere ve ek pencereler, zamana göre sıralı
+    pub fn windows(&self) -> Vec<Job> {
+        let mut windows = vec![self.main_job()];
+        windows.extend(self.jobs.iter().cloned());
+        windows.sort_by_key(|job| job.time);
+        windows
+    }
+
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.username)
+    }
+}
+
+/// config.json ya tek bir hesabın ayarlarını ya da `profiles` altında birden fazla hesabı tutar
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ConfigFile {
+    Profiles { profiles: Vec<Config> },
+    Single(Box<Config>),
+}
+
+impl ConfigFile {
+    pub fn into_profiles(self) -> Vec<Config> {
+        match self {
+            ConfigFile::Profiles { profiles } => profiles,
+            ConfigFile::Single(config) => vec![*config],
         }
     }
 }
 
 impl From<MakeConfigArgs> for Config {
     fn from(value: MakeConfigArgs) -> Self {
-        // ASSUME INPUT IS IN UTC+3 TRT TIME
-        let time = DateTime::from_timestamp(
-            value.time.as_secs() as i64 - Config::TRT_OFFSET_SECONDS,
-            value.time.subsec_nanos(),
-        )
-        .unwrap()
-        .with_timezone(&Config::TRT_TIMEZONE);
-
+        // clap requires these unless --interactive, and the wizard fills them in otherwise
         Config::new(
-            value.username,
-            value.password,
-            time,
+            value.username.expect("username"),
+            value.password.expect("password"),
+            value.time.expect("time"),
             value.crn_list,
             value.scrn_list,
         )
     }
 }
 
+/// Her aşama kendi süre sınırlarıyla ayrı bir bağlantı havuzu kullanır, çerezler ortak
 #[derive(Debug)]
-pub struct Requester {
-    config: Config,
-    client: Client,
+struct Clients {
+    login: Client,
+    jwt: Client,
+    selection: Client,
+    /// Ek istekler bağlantı saklamaz, her biri takılmış olabilecek bağlantılar yerine yeni bir
+    /// bağlantıdan gider
+    hedge: Client,
 }
 
-fn now_trt() -> DateTime<FixedOffset> {
-    Utc::now().with_timezone(&Config::TRT_TIMEZONE)
+impl Clients {
+    fn new(
+        timeouts: &Timeouts,
+        cookies: &Arc<CookieStoreMutex>,
+        resolver: &Arc<PinnedResolver>,
+    ) -> Self {
+        Self {
+            login: timeouts.login.client(cookies.clone(), resolver.clone()),
+            jwt: timeouts.jwt.client(cookies.clone(), resolver.clone()),
+            selection: timeouts.selection.client(cookies.clone(), resolver.clone()),
+            hedge: timeouts
+                .selection
+                .builder(cookies.clone(), resolver.clone())
+                .pool_max_idle_per_host(0)
+                .build()
+                .expect("Client::builder()"),
+        }
+    }
 }
 
-fn print_time_trt() {
-    println!("Şuan saat {}", now_trt());
+#[derive(Debug)]
+pub struct Requester {
+    config: Config,
+    clients: Clients,
+    resolver: Arc<PinnedResolver>,
+    cookies: Arc<CookieStoreMutex>,
+    /// Uyku ya da saat sıçramasından sonra yeniden ölçülebilir
+    clock: Arc<Mutex<ServerClock>>,
+    session_cache: SessionCache,
+    reuse_session: bool,
+    imported_session: Option<ImportedSession>,
+    login_lead: Duration,
+    login_retries: u32,
+    hedging: Option<Hedging>,
+    hedges_left: AtomicU32,
+    burst: Vec<i64>,
+    burst_log: BurstLog,
+    probe: bool,
+    history: History,
+    report_format: ReportFormat,
+}
+
+/// Açılış etrafında gönderilen tek bir istek
+struct BurstAttempt {
+    offset_ms: i64,
+    send_error: Duration,
+    sent_at: chrono::DateTime<FixedOffset>,
+    latency: Duration,
+    outcome: AttemptOutcome,
 }
 
 impl Requester {
     const COURSE_SELECT_URL: &str = "https://obs.itu.edu.tr/api/ders-kayit/v21";
-    const LOGIN_URL: &str = "https://girisv3.itu.edu.tr";
+    pub const LOGIN_URL: &str = "https://girisv3.itu.edu.tr";
     const FETCH_JWT_URL: &str = "https://obs.itu.edu.tr/ogrenci/auth/jwt";
+    const REGISTERED_COURSES_URL: &str =
+        "https://obs.itu.edu.tr/api/ders-kayit/v21/kayitli-dersler";
     /// Kepler'de ders seçerken 3 saniye beklememız gerekiyor
-    const REQUEST_DELTA: std::time::Duration = std::time::Duration::new(3, 0);
+    const REQUEST_DELTA: Duration = Duration::new(3, 0);
+    /// Giriş, ders seçiminden bu kadar önce yapılır
+    const DEFAULT_LOGIN_LEAD: Duration = Duration::from_secs(60);
+    const DEFAULT_LOGIN_RETRIES: u32 = 3;
+    /// Başarısız girişten sonraki ilk bekleme, her denemede ikiye katlanır
+    const LOGIN_BACKOFF: Duration = Duration::from_secs(1);
+    const MAX_LOGIN_BACKOFF: Duration = Duration::from_secs(8);
+    /// Saat farkı yüzünden erken gönderilmemesi için
+    const ERROR_MARGIN: TimeDelta = TimeDelta::new(0, 1000000).expect("1ms");
+    const TRY_COUNT: u64 = 100;
+    const OBS_HOST: &str = "obs.itu.edu.tr";
+    const WARM_URL: &str = "https://obs.itu.edu.tr";
+    /// Açılışta hazır bekleyecek bağlantı sayısı
+    const WARM_CONNECTIONS: usize = 2;
+    /// Sunucu boştaki bağlantıları kapatmasın diye bu aralıkla hafif istek gönderilir
+    const KEEP_WARM_INTERVAL: Duration = Duration::from_secs(4);
+    /// Açılış anında bağlantılar boş olsun diye son hafif istek en geç bu kadar önce gönderilir
+    const KEEP_WARM_STOP: TimeDelta = TimeDelta::seconds(1);
+    /// Uzun beklemeler bu uzunlukta parçalara bölünür, her parçadan sonra saat kontrol edilir
+    const WAIT_SLICE: Duration = Duration::from_secs(60);
+    /// Duvar saati ile monoton saat arasında bundan büyük fark saat sıçraması ya da uyku demek
+    const CLOCK_JUMP_THRESHOLD: TimeDelta = TimeDelta::seconds(2);
+    /// Yoklama açıksa ilk deneme açılıştan bu kadar önce gönderilir, iki deneme aralığı
+    const PROBE_LEAD: TimeDelta = TimeDelta::seconds(6);
+    /// Sunucu bu kadar geç açılmazsa yoklama bırakılır
+    const PROBE_GIVE_UP: TimeDelta = TimeDelta::minutes(10);
+    /// Zamanı bundan daha önce geçmiş pencereler, yarım kalmış bir çalıştırması yoksa atlanır
+    const PAST_WINDOW_GRACE: TimeDelta = TimeDelta::minutes(5);
 
     pub fn new(config: Config) -> Self {
-        let client = Client::builder()
-            .cookie_store(true)
-            .build()
-            .expect("Client::builder()");
-        Self { config, client }
+        let cookies = Arc::new(CookieStoreMutex::default());
+        let resolver = Arc::new(PinnedResolver::default());
+        let clients = Clients::new(&config.timeouts, &cookies, &resolver);
+        let session_cache = SessionCache::for_user(&config.username);
+        Self {
+            config,
+            clients,
+            resolver,
+            cookies,
+            clock: Arc::new(Mutex::new(ServerClock::default())),
+            session_cache,
+            reuse_session: false,
+            imported_session: None,
+            login_lead: Self::DEFAULT_LOGIN_LEAD,
+            login_retries: Self::DEFAULT_LOGIN_RETRIES,
+            hedging: None,
+            hedges_left: AtomicU32::new(0),
+            burst: Vec::new(),
+            burst_log: BurstLog::default(),
+            probe: false,
+            history: History::default(),
+            report_format: ReportFormat::Markdown,
+        }
+    }
+
+    /// Geçerli bir kayıtlı oturum varsa giriş yapmadan onu kullanır
+    pub fn with_session_reuse(mut self, reuse_session: bool) -> Self {
+        self.reuse_session = reuse_session;
+        self
+    }
+
+    /// Tarayıcıdan alınan çerezler ve/veya token ile giriş adımı atlanır
+    pub fn with_imported_session(mut self, imported_session: ImportedSession) -> Self {
+        if let Some(cookies) = imported_session.cookies {
+            *self.cookies.lock().expect("cookie store lock") = cookies;
+        }
+        self.imported_session = Some(ImportedSession {
+            cookies: None,
+            jwt: imported_session.jwt,
+        });
+        self
+    }
+
+    /// Girişin ders seçiminden ne kadar önce yapılacağı
+    pub fn with_login_lead(mut self, login_lead: Duration) -> Self {
+        self.login_lead = login_lead;
+        self
+    }
+
+    /// Giriş ya da API Token alma başarısız olursa kaç kere daha denenecek
+    pub fn with_login_retries(mut self, login_retries: u32) -> Self {
+        self.login_retries = login_retries;
+        self
+    }
+
+    /// Ders seçim isteği geç cevap verirse yeni bir bağlantıdan ikinci bir istek gönderilir
+    pub fn with_hedging(mut self, hedging: Option<Hedging>) -> Self {
+        self.hedging = hedging;
+        self
+    }
+
+    /// Açılışa göre ms cinsinden ofsetler; her birinde bir istek gönderilir, sonra normal aralıkla devam edilir
+    pub fn with_burst(mut self, mut burst: Vec<i64>) -> Self {
+        burst.sort();
+        self.burst = burst;
+        self
+    }
+
+    /// Denemeler açılıştan önce başlar, zaman engeli cevapları gerçek sonuçlara dönene kadar sayılmaz
+    pub fn with_probe(mut self, probe: bool) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Her pencerenin sonunda yazılan raporun biçimi
+    pub fn with_report_format(mut self, report_format: ReportFormat) -> Self {
+        self.report_format = report_format;
+        self
+    }
+
+    /// Birden fazla profil aynı ölçülmüş sunucu saatini paylaşır, biri yeniden ölçerse hepsi
+    /// yeni farkı kullanır
+    pub fn with_clock(mut self, clock: Arc<Mutex<ServerClock>>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn label(&self) -> &str {
+        self.config.label()
+    }
+
+    fn clock(&self) -> ServerClock {
+        *self.clock.lock().expect("clock lock")
+    }
+
+    fn until(&self, time: chrono::DateTime<FixedOffset>) -> TimeDelta {
+        let now = self.clock().now_trt();
+        time.signed_duration_since(now)
+    }
+
+    /// Profiller aynı anda çalıştığı için her satırın başına profilin adı yazılır
+    fn log(&self, message: impl Display) {
+        for line in message.to_string().lines() {
+            println!("[{}] {}", self.label(), line);
+        }
     }
 
-    fn until(&self) -> TimeDelta {
-        let now = now_trt();
-        self.config.time.signed_duration_since(now)
+    fn print_time_trt(&self) {
+        self.log(format!("Şuan saat {}", self.clock().now_trt()));
     }
 
-    pub async fn run(&self) -> Result<(), Box<dyn Error>> {
-        let until = self.until();
-        println!("Ders seçimine {} var", until);
+    /// Tüm pencereleri sırayla çalıştırır, her pencere için yeniden giriş yapılır
+    pub async fn run(&self) -> Vec<JobOutcome> {
+        let windows = self.config.windows();
+        let mut outcomes = Vec::new();
+
+        for (i, job) in windows.into_iter().enumerate() {
+            if self.is_stale(&job) {
+                self.log(format!(
+                    "{} penceresinin zamanı {} önce geçmiş, atlanıyor.",
+                    job.label(),
+                    self.since(job.time)
+                ));
+                continue;
+            }
+            if i > 0 {
+                self.log(format!("Sıradaki pencere: {}", job.label()));
+            }
+
+            let mut record = RunRecord::new(&self.config, &job, self.clock().now_trt());
+            let result = self.run_job(&job, &mut record).await;
+            if let Err(e) = &result {
+                self.log(format!("{} penceresi hatalı sonlandı: {}", job.label(), e));
+            }
+            record.finish(&result, self.clock().now_trt());
+            record.id = self.save_history(&record);
+            self.save_report(&record);
+            outcomes.push(JobOutcome { job, result });
+        }
+
+        outcomes
+    }
+
+    async fn run_job(
+        &self,
+        job: &Job,
+        record: &mut RunRecord,
+    ) -> Result<RegistrationReport, Box<dyn Error>> {
+        if let Some(hedging) = self.hedging {
+            self.hedges_left.store(hedging.max_hedges, Ordering::SeqCst);
+        }
 
-        const ONE_MINUTE_DELTA: TimeDelta =
-            TimeDelta::new(60, 0).expect("one minute delta should not fail");
-        let sleep_time = until - ONE_MINUTE_DELTA;
+        let until = self.until(job.time);
+        self.log(format!("Ders seçimine {} var", until));
+
+        let login_lead = humantime::format_duration(self.login_lead);
+        let sleep_time = until - self.login_lead_delta();
 
         match sleep_time.to_std() {
-            Ok(sleep_time) => {
-                println!("Ders seçimine 1 dakika kalana kadar bekleniyor...");
-                thread::sleep(sleep_time);
+            Ok(_) => {
+                self.log(format!(
+                    "Ders seçimine {} kalana kadar bekleniyor...",
+                    login_lead
+                ));
+                self.wait_until(job.time - self.login_lead_delta()).await;
             }
-            Err(_) => println!("Ders seçimine 1 dakikadan az var, program başlatılıyor..."),
+            Err(_) => self.log(format!(
+                "Ders seçimine {}dan az var, program başlatılıyor...",
+                login_lead
+            )),
         }
 
-        print_time_trt();
-        println!("Kepler'e giriş yapılıyor...");
+        self.print_time_trt();
+        record.phase("Giriş", self.clock().now_trt());
 
-        self.login().await?;
+        let journal = StateJournal::for_job(&self.config.username, job);
+        let mut state = self.load_state(&journal, job);
 
-        println!("Kepler'e giriş başarılı!");
+        let mut jwt = match self.restore_session(&state) {
+            Some(jwt) => jwt,
+            None => self.authenticate_before(job.time).await?,
+        };
+        self.remember_session(&mut state, &jwt);
+        self.journal(&journal, &state);
+        record.phase("Oturum hazır", self.clock().now_trt());
 
-        println!("API Token alınıyor...");
+        let pending = state.pending_job(job);
+        if !state.crns.is_empty() && pending.crn_list.is_empty() && pending.scrn_list.is_empty() {
+            self.log("Tüm CRN'ler önceki çalıştırmada sonuçlanmış, denemeler atlanıyor.");
+            return Ok(self.final_report(&jwt, job, &state, record).await);
+        }
 
-        let jwt = self.fetch_jwt().await?;
+        // açılıştan sonra yarım kalmış bir çalıştırmaya devam ediliyorsa açılış hazırlığı tekrarlanmaz
+        let resuming = state.attempts > 0 && self.until(job.time) < TimeDelta::zero();
+
+        // yoklama açıksa denemeler açılıştan biraz önce başlar
+        let start_time = if self.probe {
+            job.time - Self::PROBE_LEAD
+        } else {
+            job.time
+        };
 
-        println!("API Token başarılı bir şekilde alındı!");
+        if !resuming {
+            record.phase("Bağlantı hazırlığı", self.clock().now_trt());
+            self.warm_up().await;
+            self.keep_warm_until(start_time).await;
+            record.phase("Açılış bekleniyor", self.clock().now_trt());
+        }
 
-        let until = self.until();
-        println!("Ders seçimine {} var", until);
+        // gövde ve token açılıştan önce hazırlanır, açılışta sadece gönderilir
+        let mut request = self.build_course_selection_request(&jwt, &pending)?;
 
-        const ERROR_MARGIN: TimeDelta = TimeDelta::new(0, 1000000).expect("1ms");
-        let sleep_time = until + ERROR_MARGIN;
+        let until = self.until(start_time);
+        self.log(format!("Ders seçimine {} var", self.until(job.time)));
+        self.log(format!("Ders seçimi {} kere denenecek.", Self::TRY_COUNT));
 
-        match sleep_time.to_std() {
-            Ok(sleep_time) => {
-                println!("Ders seçimine kadar bekleniyor...");
-                thread::sleep(sleep_time);
+        let resolutions_before_fire = self.resolver.resolutions();
+        let deadline = if resuming {
+            self.log(format!(
+                "Açılış {} önce geçmiş, yarım kalan denemelere devam ediliyor.",
+                self.since(job.time)
+            ));
+            None
+        } else if self.burst.is_empty() {
+            match (until + Self::ERROR_MARGIN).to_std() {
+                Ok(sleep_time) => {
+                    self.log("Ders seçimine kadar bekleniyor...");
+                    Some(clock::sleep_precise(sleep_time).await)
+                }
+                Err(_) => {
+                    self.log("Ders seçimi başlamış! Program başlatılıyor...");
+                    None
+                }
+            }
+        } else {
+            self.log("Açılış denemelerine kadar bekleniyor...");
+            let attempts = self
+                .fire_burst(&request, job, until + Self::ERROR_MARGIN, |attempt| {
+                    state.record(&attempt.outcome);
+                    self.journal(&journal, &state);
+                    record.attempt(
+                        state.attempts,
+                        Some(attempt.offset_ms),
+                        attempt.sent_at,
+                        attempt.latency,
+                        &attempt.outcome,
+                    );
+                })
+                .await;
+
+            let abort = attempts.iter().find_map(|attempt| {
+                let (rule, action) = self.config.policy.decide(&attempt.outcome);
+                (action == Action::Abort).then_some((rule, action))
+            });
+            if let Some((rule, action)) = abort {
+                self.log(format!("Politika ({}): {}", rule, action));
+                self.log("Denemeler durduruluyor.");
+                return Ok(self.final_report(&jwt, job, &state, record).await);
+            }
+            self.report_connection_reuse(resolutions_before_fire);
+            self.log("3 saniye bekleniyor...");
+            sleep(Self::REQUEST_DELTA).await;
+            None
+        };
+
+        let mut tracker = PolicyTracker::default();
+        let mut probing = self.probe && !resuming;
+        let mut last_blocked = None;
+        // önceki çalıştırmadan devam ediliyorsa yapılmış denemeler de sayılır
+        let mut attempt = state.attempts;
+        let mut counted = state.attempts;
+
+        // açılış anı ile ilk gönderim arasında yazdırma yapılmaz
+        while counted < Self::TRY_COUNT {
+            attempt += 1;
+            let sent_at = Instant::now();
+            let sent_at_server = self.clock().now_trt();
+            let outcome = self.send_course_selection(&request).await;
+            let latency = sent_at.elapsed();
+
+            self.log(format!("{}. Deneme", attempt));
+            if let (1, Some(deadline)) = (attempt, deadline) {
+                self.log(format!(
+                    "Gönderim sapması: {} µs",
+                    sent_at.saturating_duration_since(deadline).as_micros()
+                ));
+                self.report_connection_reuse(resolutions_before_fire);
+            }
+            self.log(format!("{}", outcome));
+            state.record(&outcome);
+            self.journal(&journal, &state);
+            record.attempt(attempt, None, sent_at_server, latency, &outcome);
+
+            if probing {
+                if outcome.is_time_blocked() {
+                    last_blocked = Some(sent_at_server);
+                } else if outcome.response().is_some() {
+                    probing = false;
+                    self.report_opening(job, last_blocked, sent_at_server);
+                }
+                if probing && self.until(job.time) < -Self::PROBE_GIVE_UP {
+                    self.log(format!(
+                        "Sunucu ayarlanan zamandan {} dakika sonra da açılmadı, denemeler durduruluyor.",
+                        Self::PROBE_GIVE_UP.num_minutes()
+                    ));
+                    break;
+                }
+            }
+            // açılış beklenirken yapılan denemeler sayılmaz
+            if !probing {
+                counted += 1;
+            }
+
+            let Some(wait) = self
+                .follow_policy(&outcome, &mut tracker, &pending, &mut jwt, &mut request)
+                .await
+            else {
+                self.log("Denemeler durduruluyor.");
+                break;
+            };
+            self.remember_session(&mut state, &jwt);
+            self.log(format!(
+                "{} bekleniyor...",
+                humantime::format_duration(wait)
+            ));
+            sleep(wait).await;
+        }
+
+        state.finished = true;
+        self.journal(&journal, &state);
+
+        Ok(self.final_report(&jwt, job, &state, record).await)
+    }
+
+    /// Geçmişteki bir zamandan bu yana geçen süre, saniyeye yuvarlanmış
+    fn since(&self, time: chrono::DateTime<FixedOffset>) -> humantime::FormattedDuration {
+        let seconds = (-self.until(time)).num_seconds().max(0) as u64;
+        humantime::format_duration(Duration::from_secs(seconds))
+    }
+
+    /// Zamanı geçmiş bir pencere hemen çalıştırılırsa bırakmalar dahil tüm denemeler istenmeden
+    /// gönderilir; sadece yarım kalmış bir çalıştırmaya devam edilebilir
+    fn is_stale(&self, job: &Job) -> bool {
+        if self.until(job.time) >= -Self::PAST_WINDOW_GRACE {
+            return false;
+        }
+        let journal = StateJournal::for_job(&self.config.username, job);
+        !matches!(journal.load(), Ok(Some(state)) if !state.finished)
+    }
+
+    /// Aynı pencere için önceki bir çalıştırmanın durumu varsa oradan devam edilir
+    fn load_state(&self, journal: &StateJournal, job: &Job) -> RunState {
+        match journal.load() {
+            Ok(Some(previous)) => {
+                let state = RunState::resume(previous, job);
+                self.log(format!(
+                    "Önceki çalıştırmanın durumu bulundu ({} deneme yapılmış).",
+                    state.attempts
+                ));
+                let settled = state.settled();
+                if !settled.is_empty() {
+                    self.log(format!(
+                        "Sonuçlanmış CRN'ler atlanacak: {}",
+                        settled.join(", ")
+                    ));
+                }
+                state
+            }
+            Ok(None) => RunState::new(job),
+            Err(e) => {
+                self.log(format!(
+                    "Önceki çalıştırmanın durumu okunamadı, baştan başlanıyor: {}",
+                    e
+                ));
+                RunState::new(job)
+            }
+        }
+    }
+
+    /// Önceki çalıştırmanın oturumu hâlâ geçerliyse giriş yapılmaz
+    fn restore_session(&self, state: &RunState) -> Option<String> {
+        let session = state.session.as_ref().filter(|s| s.is_valid())?;
+        let cookies = session.cookie_store().ok()?;
+        *self.cookies.lock().expect("cookie store lock") = cookies;
+        self.log("Önceki çalıştırmanın oturumu kullanılıyor.");
+        Some(session.jwt.clone())
+    }
+
+    fn remember_session(&self, state: &mut RunState, jwt: &str) {
+        if state.session.as_ref().is_some_and(|s| s.jwt == jwt) {
+            return;
+        }
+        let cookies = self.cookies.lock().expect("cookie store lock");
+        state.session = CachedSession::new(&cookies, jwt).ok();
+    }
+
+    fn journal(&self, journal: &StateJournal, state: &RunState) {
+        if let Err(e) = journal.save(state) {
+            self.log(format!("Çalıştırma durumu kaydedilemedi: {}", e));
+        }
+    }
+
+    fn save_history(&self, record: &RunRecord) -> Option<i64> {
+        match self.history.record(record) {
+            Ok(id) => {
+                self.log(format!(
+                    "Çalıştırma #{} olarak {} dosyasına kaydedildi.",
+                    id,
+                    self.history.path().display()
+                ));
+                Some(id)
+            }
+            Err(e) => {
+                self.log(format!("Çalıştırma geçmişe kaydedilemedi: {}", e));
+                None
+            }
+        }
+    }
+
+    fn save_report(&self, record: &RunRecord) {
+        let name = match record.id {
+            Some(id) => format!("run-{}", id),
+            None => format!("run-{}", record.started_at.format("%Y%m%d-%H%M%S")),
+        };
+        let report = Report::new(record).redacting(&[&self.config.password]);
+        match report.write(&name, self.report_format) {
+            Ok(path) => self.log(format!("Rapor {} dosyasına yazıldı.", path.display())),
+            Err(e) => self.log(format!("Rapor yazılamadı: {}", e)),
+        }
+    }
+
+    /// Kayıtlı dersler alınamazsa denemeler boşa gitmesin diye sonuç deneme cevaplarındaki
+    /// kodlardan çıkarılır
+    async fn final_report(
+        &self,
+        jwt: &str,
+        job: &Job,
+        state: &RunState,
+        record: &mut RunRecord,
+    ) -> RegistrationReport {
+        record.phase("Son durum kontrolü", self.clock().now_trt());
+        self.log("Son durum kontrol ediliyor...");
+        match self.fetch_registered_courses(jwt).await {
+            Ok(registered) => {
+                print!("{}", registered);
+                RegistrationReport::new(&registered, &job.crn_list, &job.scrn_list)
+            }
+            Err(e) => {
+                self.log(format!("Kayıtlı dersler alınamadı: {}", e));
+                self.log("Sonuçlar deneme cevaplarındaki kodlara göre yazılıyor.");
+                record.phase(
+                    "Kayıtlı dersler doğrulanamadı, sonuç deneme cevaplarından",
+                    self.clock().now_trt(),
+                );
+                state.report(job)
+            }
+        }
+    }
+
+    /// Zaman engeli veren son deneme ile ilk gerçek cevap arasında sunucu açılmıştır
+    fn report_opening(
+        &self,
+        job: &Job,
+        last_blocked: Option<chrono::DateTime<FixedOffset>>,
+        first_open: chrono::DateTime<FixedOffset>,
+    ) {
+        let drift = first_open
+            .signed_duration_since(job.time)
+            .num_milliseconds();
+        match last_blocked {
+            Some(last_blocked) => self.log(format!(
+                "Açılış algılandı: {} ile {} arasında (ayarlanan zamana göre {:+} ms).",
+                last_blocked, first_open, drift
+            )),
+            None => self.log(format!(
+                "Sunucu ilk denemede açıktı ({}, ayarlanan zamana göre {:+} ms).",
+                first_open, drift
+            )),
+        }
+        self.log(format!(
+            "{} deneme bu andan itibaren sayılıyor.",
+            Self::TRY_COUNT
+        ));
+    }
+
+    /// Açılıştan beri yeni ad çözümlemesi yapılmadıysa istekler hazır bağlantılardan gitmiştir
+    fn report_connection_reuse(&self, resolutions_before_fire: u64) {
+        if self.resolver.resolutions() == resolutions_before_fire {
+            self.log("Açılış isteği hazır bekleyen bir bağlantıyla gönderildi.");
+        } else {
+            self.log("Açılış isteği için yeni bağlantı açıldı.");
+        }
+    }
+
+    /// Açılışa göre verilen ofsetlerde (ms) birer istek gönderir. Her sonuç geldiği anda
+    /// `on_attempt` ile bildirilir, böylece program yarıda kapanırsa gelen sonuçlar kaybolmaz.
+    /// Sonuçlar sonraki dönemlerde ofsetleri ayarlamak için de kaydedilir.
+    async fn fire_burst(
+        &self,
+        request: &Request,
+        job: &Job,
+        fire_in: TimeDelta,
+        mut on_attempt: impl FnMut(&BurstAttempt),
+    ) -> Vec<BurstAttempt> {
+        let fire_instant = Instant::now() + fire_in.to_std().unwrap_or_default();
+
+        let mut in_flight: FuturesUnordered<_> = self
+            .burst
+            .iter()
+            .map(|&offset_ms| async move {
+                let offset = Duration::from_millis(offset_ms.unsigned_abs());
+                let target = if offset_ms >= 0 {
+                    fire_instant + offset
+                } else {
+                    fire_instant.checked_sub(offset).unwrap_or(fire_instant)
+                };
+
+                clock::sleep_precise(target.saturating_duration_since(Instant::now())).await;
+                let sent_instant = Instant::now();
+                let sent_at = self.clock().now_trt();
+                let outcome = self.send_course_selection(request).await;
+                BurstAttempt {
+                    offset_ms,
+                    send_error: sent_instant.saturating_duration_since(target),
+                    sent_at,
+                    latency: sent_instant.elapsed(),
+                    outcome,
+                }
+            })
+            .collect();
+
+        let mut attempts = Vec::new();
+        let mut log_error = None;
+        while let Some(attempt) = in_flight.next().await {
+            self.log(format!(
+                "Açılış denemesi {:+} ms (sapma {} µs):",
+                attempt.offset_ms,
+                attempt.send_error.as_micros()
+            ));
+            self.log(format!("{}", attempt.outcome));
+            on_attempt(&attempt);
+
+            let record = BurstRecord {
+                username: self.config.username.clone(),
+                opening: job.time,
+                offset_ms: attempt.offset_ms,
+                send_error_us: attempt.send_error.as_micros(),
+                outcome: attempt.outcome.kind().to_string(),
+            };
+            if let Err(e) = self.burst_log.append(&[record]) {
+                log_error = Some(e);
+            }
+            attempts.push(attempt);
+        }
+        attempts.sort_by_key(|attempt| attempt.offset_ms);
+
+        let too_early: Vec<String> = attempts
+            .iter()
+            .filter(|attempt| attempt.outcome.is_time_blocked())
+            .map(|attempt| format!("{:+} ms", attempt.offset_ms))
+            .collect();
+        if !too_early.is_empty() {
+            self.log(format!(
+                "Zaman engeline takılan (erken kalan) ofsetler: {}",
+                too_early.join(", ")
+            ));
+        }
+
+        match log_error {
+            None => self.log(format!(
+                "Açılış denemeleri {} dosyasına kaydedildi.",
+                self.burst_log.path().display()
+            )),
+            Some(e) => self.log(format!("Açılış denemeleri kaydedilemedi: {}", e)),
+        }
+
+        attempts
+    }
+
+    /// Uzun beklemeyi kısa parçalara böler. Bilgisayar uyursa monoton saat durur, tek parça bir
+    /// bekleme geç uyanır; her uyanışta duvar saati ile karşılaştırılır, sıçrama varsa sunucu farkı
+    /// yeniden ölçülür.
+    async fn wait_until(&self, target: chrono::DateTime<FixedOffset>) {
+        while let Ok(remaining) = self.until(target).to_std() {
+            if remaining.is_zero() {
+                break;
             }
-            Err(_) => println!("Ders seçimi başlamış! Program başlatılıyor..."),
+            let slice = remaining.min(Self::WAIT_SLICE);
+
+            let clock_before = self.clock();
+            let wall_before = Utc::now();
+            let monotonic_before = Instant::now();
+            sleep(slice).await;
+            let wall_elapsed = Utc::now() - wall_before;
+            let monotonic_elapsed =
+                TimeDelta::from_std(monotonic_before.elapsed()).expect("elapsed should fit");
+
+            let jump = wall_elapsed - monotonic_elapsed;
+            if jump.abs() <= Self::CLOCK_JUMP_THRESHOLD {
+                continue;
+            }
+            self.log(format!(
+                "Saat sıçraması ya da uyku algılandı: duvar saati {} ms ilerledi, beklenen {} ms.",
+                wall_elapsed.num_milliseconds(),
+                monotonic_elapsed.num_milliseconds()
+            ));
+
+            // aynı sıçramayı gören diğer profillerden biri saati çoktan yeniden ölçmüş olabilir
+            if self.clock() == clock_before {
+                self.resync_clock().await;
+            }
+            self.print_time_trt();
+            self.log("Zamanlama yeniden hesaplandı.");
         }
 
-        println!("Ders seçiliyor...");
+        if self.until(target) < -Self::CLOCK_JUMP_THRESHOLD {
+            self.log(format!(
+                "Hedeflenen zaman {} geride kaldı, hemen devam ediliyor.",
+                -self.until(target)
+            ));
+        }
+    }
 
-        let request = self.build_course_selection_request(&jwt)?;
+    /// Sunucu farkını yeniden ölçer; yeni fark saati paylaşan tüm profillere geçer
+    async fn resync_clock(&self) {
+        let old = self.clock();
+        let new = match ServerClock::sync(&self.clients.selection).await {
+            Ok(clock) => clock,
+            Err(e) => {
+                self.log(format!(
+                    "Sunucu saati yeniden ölçülemedi, eski fark kullanılıyor: {}",
+                    e
+                ));
+                return;
+            }
+        };
+        *self.clock.lock().expect("clock lock") = new;
 
-        const TRY_COUNT: u64 = 100;
-        println!("{} kere denenecek.", TRY_COUNT);
+        self.log(format!(
+            "Sunucu saati ile fark: {} ms -> {} ms.",
+            old.offset().num_milliseconds(),
+            new.offset().num_milliseconds()
+        ));
+    }
 
-        for i in 1..=TRY_COUNT {
-            let res = self.send_request(&request).await?;
+    /// OBS adresini önceden çözüp sabitler ve ders seçiminde kullanılacak bağlantıları açar
+    async fn warm_up(&self) {
+        match self.resolver.pin(Self::OBS_HOST).await {
+            Ok(addrs) => self.log(format!(
+                "{} adresi sabitlendi: {}",
+                Self::OBS_HOST,
+                addrs
+                    .iter()
+                    .map(|a| a.ip().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            Err(e) => self.log(format!("{} adresi çözülemedi: {}", Self::OBS_HOST, e)),
+        }
 
-            let res_body: CourseSelectionResponseBody =
-                serde_json::from_slice(&res.bytes().await?)?;
+        let warm = self.send_warm_requests().await;
+        self.log(format!("{} bağlantı hazırlandı.", warm));
+    }
 
-            println!("{}. Deneme", i);
-            println!("{}", res_body);
-            println!("3 saniye bekleniyor...");
-            thread::sleep(Self::REQUEST_DELTA);
+    /// Ders seçim havuzunda hazır bağlantı olması için aynı anda hafif istekler gönderir, başarılı olanları sayar
+    async fn send_warm_requests(&self) -> usize {
+        join_all(
+            vec![&self.clients.selection; Self::WARM_CONNECTIONS]
+                .into_iter()
+                .map(|client| client.head(Self::WARM_URL).send()),
+        )
+        .await
+        .iter()
+        .filter(|res| res.is_ok())
+        .count()
+    }
+
+    /// Ders seçimine kadar bağlantıların kapanmaması için ara ara hafif istek gönderir
+    async fn keep_warm_until(&self, fire_time: chrono::DateTime<FixedOffset>) {
+        let interval = TimeDelta::from_std(Self::KEEP_WARM_INTERVAL).expect("keep warm interval");
+
+        while self.until(fire_time) - interval > Self::KEEP_WARM_STOP {
+            sleep(Self::KEEP_WARM_INTERVAL).await;
+            self.send_warm_requests().await;
+        }
+    }
+
+    /// Ders seçimine kadar olan her şeyi yapar ama ders seçimini göndermez. Gönderilecek istek gizli
+    /// bilgiler gizlenerek yazdırılır. `with_login` verilmezse giriş de yapılmaz.
+    pub async fn dry_run(&self, with_login: bool) -> Result<(), Box<dyn Error>> {
+        let jwt = if with_login {
+            self.authenticate().await?
+        } else {
+            println!("Giriş yapılmayacak, API Token yerine yer tutucu kullanılıyor.");
+            "<API Token>".to_string()
+        };
+
+        for job in self.config.windows() {
+            if self.is_stale(&job) {
+                println!("--- {} zamanı geçmiş, atlanacak ---", job.label());
+                continue;
+            }
+            println!("--- {} isteği ---", job.label());
+            let request = self.build_course_selection_request(&jwt, &job)?;
+            print!("{}", self.describe_request(&request, with_login));
         }
 
         Ok(())
     }
 
-    fn build_login_form(document: Html, config: Config) -> LoginFormBody {
-        let hidden_fields = LoginFormHiddenFields::from(document);
-        let input_fields = LoginFormInputFields::from(config);
+    /// Çalıştırmanın ne yapacağını beklemeye başlamadan önce yazdırır
+    pub fn print_plan(&self) {
+        println!("===== Plan: {} =====", self.label());
+        println!("Şuan saat (sunucu): {}", self.clock().now_trt());
+        println!(
+            "Sunucu saati ile fark: {} ms (zamanlar sunucu saatine göredir)",
+            self.clock().offset().num_milliseconds()
+        );
+        println!("Giriş: {}", self.authentication_plan());
+        let timeouts = &self.config.timeouts;
+        println!(
+            "Süre sınırları (bağlantı/toplam): giriş {}/{} ms, API Token {}/{} ms, ders seçimi {}/{} ms",
+            timeouts.login.connect_ms,
+            timeouts.login.total_ms,
+            timeouts.jwt.connect_ms,
+            timeouts.jwt.total_ms,
+            timeouts.selection.connect_ms,
+            timeouts.selection.total_ms
+        );
+        println!(
+            "Bağlantı hazırlığı: girişten sonra {} adresi sabitlenir, {} bağlantı açılır ve {} arayla hafif isteklerle açık tutulur",
+            Self::OBS_HOST,
+            Self::WARM_CONNECTIONS,
+            humantime::format_duration(Self::KEEP_WARM_INTERVAL)
+        );
+        if let Some(hedging) = self.hedging {
+            println!(
+                "Ek istek: {} içinde cevap gelmezse yeni bağlantıdan, pencere başına en fazla {} kere",
+                humantime::format_duration(hedging.after),
+                hedging.max_hedges
+            );
+        }
+
+        for job in self.config.windows() {
+            self.print_window_plan(&job);
+        }
+
+        println!("Durma koşulları:");
+        println!(
+            "  Her pencerede {} deneme tamamlanınca durulur, sonra kayıtlı dersler kontrol edilir.",
+            Self::TRY_COUNT
+        );
+        println!("  Politika \"dur\" derse o pencere durur, sıradaki pencereye geçilir.");
+        println!("Politika:");
+        for (rule, action) in self.config.policy.effective() {
+            println!("  {}: {}", rule, action);
+        }
+    }
+
+    fn authentication_plan(&self) -> &'static str {
+        match &self.imported_session {
+            Some(ImportedSession { jwt: Some(_), .. }) => "verilen API Token kullanılacak",
+            Some(_) => "tarayıcı çerezleriyle API Token alınacak",
+            None if self.reuse_session => {
+                "geçerli kayıtlı oturum varsa o kullanılacak, yoksa giriş yapılıp API Token alınacak"
+            }
+            None => "giriş yapılıp API Token alınacak",
+        }
+    }
+
+    fn print_window_plan(&self, job: &Job) {
+        const SHOWN_ATTEMPTS: u64 = 3;
+
+        let fire_time = job.time + Self::ERROR_MARGIN;
+        let request_delta =
+            TimeDelta::from_std(Self::REQUEST_DELTA).expect("request delta should fit");
+        // açılış denemeleri varsa normal denemeler sonuncusundan sonra, yoklamada açılıştan önce başlar
+        let first_attempt = match self.burst.last() {
+            Some(&last_offset) => fire_time + TimeDelta::milliseconds(last_offset) + request_delta,
+            None if self.probe => fire_time - Self::PROBE_LEAD,
+            None => fire_time,
+        };
+        let attempt_time = |i: u64| first_attempt + request_delta * (i - 1) as i32;
+
+        println!("--- {} ---", job.label());
+        if self.is_stale(job) {
+            println!(
+                "ATLANACAK: açılış ({}) {} önce geçmiş",
+                job.time,
+                self.since(job.time)
+            );
+            return;
+        }
+        println!(
+            "Giriş ve API Token: {} ({} önce)",
+            job.time - self.login_lead_delta(),
+            humantime::format_duration(self.login_lead)
+        );
+        println!(
+            "  Başarısız olursa {} kere daha denenir, olmazsa geçerli kayıtlı oturum kullanılır",
+            self.login_retries
+        );
+        println!(
+            "Açılış: {} (yerel saat: {}, {} ms pay ile)",
+            fire_time,
+            self.clock().local_time(fire_time),
+            Self::ERROR_MARGIN.num_milliseconds()
+        );
+        if self.probe {
+            println!(
+                "Yoklama: denemeler açılıştan {} saniye önce başlar, zaman engeli cevapları sayılmaz",
+                Self::PROBE_LEAD.num_seconds()
+            );
+        }
+        if !self.burst.is_empty() {
+            println!(
+                "Açılış denemeleri: {} (açılışa göre), ardından normal aralık",
+                self.burst
+                    .iter()
+                    .map(|offset| format!("{:+} ms", offset))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        println!(
+            "Denemeler ({} kere, {} saniye arayla):",
+            Self::TRY_COUNT,
+            Self::REQUEST_DELTA.as_secs()
+        );
+        for i in 1..=SHOWN_ATTEMPTS.min(Self::TRY_COUNT) {
+            println!("  {}. deneme: {}", i, attempt_time(i));
+        }
+        if Self::TRY_COUNT > SHOWN_ATTEMPTS {
+            println!("  ...");
+            println!(
+                "  {}. deneme: {}",
+                Self::TRY_COUNT,
+                attempt_time(Self::TRY_COUNT)
+            );
+        }
+        println!(
+            "Son durum kontrolü: {}",
+            attempt_time(Self::TRY_COUNT) + request_delta
+        );
+
+        println!("CRN'ler:");
+        if job.crn_list.is_empty() && job.scrn_list.is_empty() {
+            println!("  (yok, boş istek gönderilecek)");
+        }
+        let swap = !job.crn_list.is_empty() && !job.scrn_list.is_empty();
+        for crn in &job.scrn_list {
+            if swap {
+                println!("  {}: bırak (eklemelerle aynı istekte, değiştirme)", crn);
+            } else {
+                println!("  {}: bırak", crn);
+            }
+        }
+        for crn in &job.crn_list {
+            println!("  {}: ekle", crn);
+        }
+
+        if job.time == self.config.time && !self.config.alternatives.is_empty() {
+            println!("Yedek programlar (otomatik denenmez, elle değiştirmek için):");
+            for (i, alternative) in self.config.alternatives.iter().enumerate() {
+                println!("  {}. {}", i + 1, alternative.join(", "));
+            }
+        }
+    }
+
+    /// İsteği gönderileceği haliyle yazar; token ve çerez değerleri gizlenir
+    fn describe_request(&self, request: &Request, redact_jwt: bool) -> String {
+        let mut description = format!("{} {}\n", request.method(), request.url());
+
+        for (name, value) in request.headers() {
+            let value = if name == reqwest::header::AUTHORIZATION && redact_jwt {
+                "Bearer <gizlendi>".to_string()
+            } else {
+                value.to_str().unwrap_or("<okunamadı>").to_string()
+            };
+            description += &format!("{}: {}\n", name, value);
+        }
+
+        let cookie_names: Vec<String> = self
+            .cookies
+            .lock()
+            .expect("cookie store lock")
+            .get_request_values(request.url())
+            .map(|(name, _)| format!("{}=<gizlendi>", name))
+            .collect();
+        if !cookie_names.is_empty() {
+            description += &format!("cookie: {}\n", cookie_names.join("; "));
+        }
+
+        if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+            description += &format!("\n{}\n", String::from_utf8_lossy(body));
+        }
+
+        description
+    }
+
+    /// Zaman beklemeden giriş yapıp ders seçimini hemen gönderir, tüm CRN'ler sonuçlanana kadar
+    /// `try_count` kere dener. Her denemede sadece henüz sonuçlanmamış CRN'ler gönderilir.
+    pub async fn select_now(&self, try_count: u64) -> Result<(), Box<dyn Error>> {
+        let mut jwt = self.authenticate().await?;
+
+        let job = self.config.main_job();
+        let mut state = RunState::new(&job);
+        let mut tracker = PolicyTracker::default();
+
+        for i in 1..=try_count {
+            let pending = state.pending_job(&job);
+            let mut request = self.build_course_selection_request(&jwt, &pending)?;
+            let outcome = self.send_course_selection(&request).await;
+            state.record(&outcome);
+
+            self.log(format!("{}. Deneme", i));
+            self.log(format!("{}", outcome));
+
+            if state.crns.values().all(|crn| crn.settled) {
+                return Ok(());
+            }
+
+            let Some(wait) = self
+                .follow_policy(&outcome, &mut tracker, &pending, &mut jwt, &mut request)
+                .await
+            else {
+                return Err(format!("{}. denemede durduruldu", i).into());
+            };
+            if i < try_count {
+                self.log(format!(
+                    "{} bekleniyor...",
+                    humantime::format_duration(wait)
+                ));
+                sleep(wait).await;
+            }
+        }
+
+        let pending = state.pending_job(&job);
+        Err(format!(
+            "{} denemede tüm işlemler tamamlanamadı, sonuçlanmayan CRN'ler: {}",
+            try_count,
+            pending
+                .crn_list
+                .iter()
+                .chain(pending.scrn_list.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .into())
+    }
+
+    /// Denemenin sonucuna politikayı uygular: gerekirse yeniden giriş yapıp isteği yeniler.
+    /// Sonraki denemeden önce beklenecek süreyi, durulması gerekiyorsa `None` döndürür.
+    async fn follow_policy(
+        &self,
+        outcome: &AttemptOutcome,
+        tracker: &mut PolicyTracker,
+        job: &Job,
+        jwt: &mut String,
+        request: &mut Request,
+    ) -> Option<Duration> {
+        let (rule, action) = self.config.policy.decide(outcome);
+        let streak = tracker.record(&rule);
+        if action != Action::Continue {
+            self.log(format!("Politika ({}): {}", rule, action));
+        }
+
+        match action {
+            Action::Abort => return None,
+            Action::Relogin => match self.relogin(job).await {
+                Ok((new_jwt, new_request)) => {
+                    *jwt = new_jwt;
+                    *request = new_request;
+                }
+                Err(e) => {
+                    self.log(format!("Yeniden giriş yapılamadı: {}", e));
+                    return None;
+                }
+            },
+            _ => (),
+        }
+
+        Some(action.delay(Self::REQUEST_DELTA, streak))
+    }
+
+    /// Kayıtlı ya da verilen oturuma bakmadan giriş yapıp yeni token ile isteği yeniden hazırlar
+    async fn relogin(&self, job: &Job) -> Result<(String, Request), Box<dyn Error>> {
+        self.log("Kepler'e yeniden giriş yapılıyor...");
+        self.login().await?;
+        let jwt = self.fetch_jwt().await?;
+        self.log("Yeni API Token alındı.");
+        self.save_session(&jwt);
+
+        let request = self.build_course_selection_request(&jwt, job)?;
+        Ok((jwt, request))
+    }
+
+    /// Giriş yapıp hesabın bu dönem kayıtlı olduğu dersleri getirir
+    pub async fn status(&self) -> Result<RegisteredCoursesResponseBody, Box<dyn Error>> {
+        let jwt = self.authenticate().await?;
+
+        self.fetch_registered_courses(&jwt).await
+    }
+
+    pub async fn fetch_registered_courses(
+        &self,
+        jwt: &str,
+    ) -> Result<RegisteredCoursesResponseBody, Box<dyn Error>> {
+        let res = self
+            .clients
+            .selection
+            .get(Self::REGISTERED_COURSES_URL)
+            .bearer_auth(jwt)
+            .send()
+            .await?;
+
+        let status = res.status();
+        let body = res.bytes().await?;
+        if !status.is_success() {
+            return Err(format!(
+                "HTTP {}: {}",
+                status,
+                attempt::excerpt(&String::from_utf8_lossy(&body))
+            )
+            .into());
+        }
+
+        serde_json::from_slice(&body).map_err(|e| {
+            format!(
+                "cevap beklenen biçimde değil ({}): {}",
+                e,
+                attempt::excerpt(&String::from_utf8_lossy(&body))
+            )
+            .into()
+        })
+    }
+
+    fn login_lead_delta(&self) -> TimeDelta {
+        TimeDelta::from_std(self.login_lead).expect("login lead should fit")
+    }
+
+    /// Giriş başarısız olursa artan aralıklarla tekrar dener. Denemeler biterse ya da bir sonraki
+    /// deneme ders seçimini kaçıracaksa geçerli bir kayıtlı oturum varsa onunla devam eder.
+    async fn authenticate_before(
+        &self,
+        fire_time: chrono::DateTime<FixedOffset>,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut backoff = Self::LOGIN_BACKOFF;
+        let mut attempt = 0;
+
+        loop {
+            let error = match self.authenticate().await {
+                Ok(jwt) => return Ok(jwt),
+                Err(e) => e,
+            };
+            self.log(format!("Giriş başarısız: {}", error));
+
+            let too_close = self.until(fire_time) < TimeDelta::from_std(backoff)?;
+            if attempt >= self.login_retries || too_close {
+                return match self.cached_session() {
+                    Some(jwt) => Ok(jwt),
+                    None => Err(error),
+                };
+            }
+
+            self.log(format!(
+                "{} sonra tekrar denenecek ({}/{})...",
+                humantime::format_duration(backoff),
+                attempt + 1,
+                self.login_retries
+            ));
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(Self::MAX_LOGIN_BACKOFF);
+            attempt += 1;
+        }
+    }
+
+    /// Giriş yapılamadığında son çare olarak kayıtlı oturum
+    fn cached_session(&self) -> Option<String> {
+        match self.session_cache.load() {
+            Ok(Some(session)) if session.is_valid() => {
+                let cookies = session.cookie_store().ok()?;
+                *self.cookies.lock().expect("cookie store lock") = cookies;
+                self.log(format!(
+                    "Giriş yapılamadı, kayıtlı oturum kullanılıyor ({} tarihine kadar geçerli).",
+                    session.expires_at().with_timezone(&Config::TRT_TIMEZONE)
+                ));
+                Some(session.jwt)
+            }
+            _ => {
+                self.log("Kullanılabilecek kayıtlı oturum yok.");
+                None
+            }
+        }
+    }
+
+    /// İstenmişse kayıtlı oturumu kullanır, yoksa giriş yapıp yeni oturumu kaydeder
+    async fn authenticate(&self) -> Result<String, Box<dyn Error>> {
+        if let Some(imported) = &self.imported_session {
+            if let Some(jwt) = &imported.jwt {
+                self.log("Verilen API Token kullanılıyor, giriş yapılmayacak.");
+                return Ok(jwt.clone());
+            }
+
+            self.log("Tarayıcı çerezleri ile API Token alınıyor...");
+            let jwt = self.fetch_jwt().await?;
+            self.log("API Token başarılı bir şekilde alındı!");
+            self.save_session(&jwt);
+            return Ok(jwt);
+        }
+
+        if self.reuse_session {
+            match self.session_cache.load() {
+                Ok(Some(session)) if session.is_valid() => {
+                    *self.cookies.lock().expect("cookie store lock") = session.cookie_store()?;
+                    self.log(format!(
+                        "Kayıtlı oturum kullanılıyor ({} tarihine kadar geçerli).",
+                        session.expires_at().with_timezone(&Config::TRT_TIMEZONE)
+                    ));
+                    return Ok(session.jwt);
+                }
+                Ok(Some(_)) => self.log("Kayıtlı oturumun süresi dolmuş."),
+                Ok(None) => self.log("Kayıtlı oturum bulunamadı."),
+                Err(e) => self.log(format!("Kayıtlı oturum okunamadı: {}", e)),
+            }
+        }
+
+        self.log("Kepler'e giriş yapılıyor...");
+        self.login().await?;
+        self.log("Kepler'e giriş başarılı!");
+
+        self.log("API Token alınıyor...");
+        let jwt = self.fetch_jwt().await?;
+        self.log("API Token başarılı bir şekilde alındı!");
+
+        self.save_session(&jwt);
+
+        Ok(jwt)
+    }
+
+    fn save_session(&self, jwt: &str) {
+        // çözümlenemeyen bir token kaydedilirse --reuse-session onu geçerli sanar
+        if session::jwt_expiry(jwt).is_none() {
+            self.log("API Token çözümlenemedi, oturum kaydedilmedi.");
+            return;
+        }
 
-        LoginFormBody::new(hidden_fields, input_fields)
+        let session = {
+            let cookies = self.cookies.lock().expect("cookie store lock");
+            CachedSession::new(&cookies, jwt)
+        };
+
+        match session.and_then(|session| self.session_cache.save(&session)) {
+            Ok(_) => (),
+            Err(e) => self.log(format!("Oturum kaydedilemedi: {}", e)),
+        }
     }
 
-    async fn login(&self) -> Result<Response, reqwest::Error> {
-        let login_page_res = self.client.get(Self::LOGIN_URL).send().await?;
-        let referer_url = login_page_res.url().to_string();
+    /// Giriş formunu gönderir. Şifre yanlışsa sunucu hata vermez, giriş sayfasını tekrar gösterir;
+    /// bu durumda giriş başarısız sayılır.
+    pub async fn login(&self) -> Result<(), Box<dyn Error>> {
+        let login_page_res = self.clients.login.get(Self::LOGIN_URL).send().await?;
+        let page_url = login_page_res.url().clone();
         let body = login_page_res.text().await?;
         let document = Html::parse_document(&body);
 
-        let login_form = Self::build_login_form(document, self.config.clone());
+        let login_form = LoginForm::parse(&document)?
+            .with_credentials(&self.config.username, &self.config.password);
 
-        Ok(self
-            .client
-            .post(&referer_url)
-            .header("Referer", referer_url)
-            .form(&login_form)
+        let res = self
+            .clients
+            .login
+            .post(login_form.action_url(&page_url)?)
+            .header("Referer", page_url.as_str())
+            .form(login_form.fields())
             .send()
-            .await?)
+            .await?;
+
+        let status = res.status();
+        if !status.is_success() {
+            return Err(format!("Giriş isteği başarısız (HTTP {})", status).into());
+        }
+        let body = res.text().await?;
+        if LoginForm::parse(&Html::parse_document(&body)).is_ok() {
+            return Err(
+                "Giriş sayfası tekrar açıldı, kullanıcı adı ya da şifre yanlış olabilir".into(),
+            );
+        }
+
+        Ok(())
     }
 
-    async fn fetch_jwt(&self) -> Result<String, reqwest::Error> {
+    /// Giriş başarısızsa OBS token yerine hata ya da giriş sayfası döndürür, bu yüzden cevabın
+    /// çözümlenebilen bir token olduğu kontrol edilir
+    pub async fn fetch_jwt(&self) -> Result<String, Box<dyn Error>> {
         // first request sets cookies
-        let _ = self.client.get(Self::FETCH_JWT_URL).send().await?;
+        let _ = self.clients.jwt.get(Self::FETCH_JWT_URL).send().await?;
 
         // second requests fetches JWT
-        let res = self.client.get(Self::FETCH_JWT_URL).send().await?;
+        let res = self.clients.jwt.get(Self::FETCH_JWT_URL).send().await?;
+        let status = res.status();
+        let body = res.text().await?;
 
-        Ok(res.text().await?)
+        if !status.is_success() {
+            return Err(format!(
+                "API Token alınamadı (HTTP {}): {}",
+                status,
+                attempt::excerpt(&body)
+            )
+            .into());
+        }
+        if session::jwt_expiry(&body).is_none() {
+            return Err(format!(
+                "API Token yerine beklenmedik bir cevap geldi: {}",
+                attempt::excerpt(&body)
+            )
+            .into());
+        }
+
+        Ok(body)
     }
 
-    fn build_course_selection_request(&self, jwt: &str) -> reqwest::Result<Request> {
-        let request_body = CourseSelectionRequestBody::from(self.config.clone());
+    fn build_course_selection_request(&self, jwt: &str, job: &Job) -> reqwest::Result<Request> {
+        let request_body = CourseSelectionRequestBody::from(job.clone());
 
-        self.client
+        self.clients
+            .selection
             .post(Self::COURSE_SELECT_URL)
             .bearer_auth(jwt)
             .json(&request_body)
             .build()
     }
 
+    /// Boş CRN listeleriyle ders seçimi gönderir, hiçbir şeyi değiştirmeden cevabın biçimini görmek için
+    pub async fn send_empty_course_selection(&self, jwt: &str) -> Result<Response, reqwest::Error> {
+        self.clients
+            .selection
+            .post(Self::COURSE_SELECT_URL)
+            .bearer_auth(jwt)
+            .json(&CourseSelectionRequestBody::new(Vec::new(), Vec::new()))
+            .send()
+            .await
+    }
+
+    /// Cevap ne olursa olsun hata vermez, sonuç sınıflandırılıp döndürülür
+    async fn send_course_selection(&self, request: &Request) -> AttemptOutcome {
+        let res = match self.send_request(request).await {
+            Ok(res) => res,
+            Err(e) => return AttemptOutcome::Network(e.to_string()),
+        };
+
+        let status = res.status();
+        match res.bytes().await {
+            Ok(body) => AttemptOutcome::classify(status, &body),
+            Err(e) => AttemptOutcome::Network(e.to_string()),
+        }
+    }
+
     async fn send_request(&self, request: &Request) -> Result<Response, reqwest::Error> {
-        RequestBuilder::from_parts(self.client.clone(), request.try_clone().unwrap())
+        let primary = Self::send_with(&self.clients.selection, request);
+
+        let Some(hedging) = self.hedging else {
+            return primary.await;
+        };
+
+        let mut primary = Box::pin(primary);
+        tokio::select! {
+            res = &mut primary => return res,
+            _ = sleep(hedging.after) => (),
+        }
+
+        let hedge_allowed = self
+            .hedges_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok();
+        if !hedge_allowed {
+            return primary.await;
+        }
+
+        self.log(format!(
+            "{} içinde cevap gelmedi, yeni bağlantıdan ek istek gönderiliyor...",
+            humantime::format_duration(hedging.after)
+        ));
+        let hedge = Box::pin(Self::send_with(&self.clients.hedge, request));
+        select_ok([primary, hedge]).await.map(|(res, _)| res)
+    }
+
+    async fn send_with(client: &Client, request: &Request) -> Result<Response, reqwest::Error> {
+        RequestBuilder::from_parts(client.clone(), request.try_clone().unwrap())
             .send()
             .await
     }
@@ -0,0 +1,425 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt::Display,
+};
+
+use chrono::{NaiveTime, Weekday};
+
+use crate::catalog::{Catalog, Section};
+
+#[derive(Debug, Clone, Default)]
+pub struct Preferences {
+    pub not_before: Option<NaiveTime>,
+    pub free_days: Vec<Weekday>,
+    pub preferred_instructors: Vec<String>,
+}
+
+impl Preferences {
+    const EARLY_SESSION_PENALTY: i64 = 10;
+    const BUSY_FREE_DAY_PENALTY: i64 = 20;
+    const FULL_SECTION_PENALTY: i64 = 5;
+    const PREFERRED_INSTRUCTOR_BONUS: i64 = 3;
+
+    fn score(&self, sections: &[&Section]) -> i64 {
+        let mut score = 0;
+
+        for section in sections {
+            for session in &section.sessions {
+                if self.not_before.is_some_and(|t| session.start < t) {
+                    score -= Self::EARLY_SESSION_PENALTY;
+                }
+            }
+
+            let instructor = section.instructor.to_lowercase();
+            if self
+                .preferred_instructors
+                .iter()
+                .any(|p| instructor.contains(&p.to_lowercase()))
+            {
+                score += Self::PREFERRED_INSTRUCTOR_BONUS;
+            }
+
+            if section.is_full() {
+                score -= Self::FULL_SECTION_PENALTY;
+            }
+        }
+
+        for day in &self.free_days {
+            if sections
+                .iter()
+                .any(|s| s.sessions.iter().any(|session| session.day == *day))
+            {
+                score -= Self::BUSY_FREE_DAY_PENALTY;
+            }
+        }
+
+        score
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Schedule<'a> {
+    pub sections: Vec<&'a Section>,
+    pub score: i64,
+}
+
+impl Schedule<'_> {
+    pub fn crn_list(&self) -> Vec<String> {
+        self.sections.iter().map(|s| s.crn.clone()).collect()
+    }
+}
+
+impl Display for Schedule<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Puan: {}", self.score)?;
+        self.sections
+            .iter()
+            .try_for_each(|s| writeln!(f, "  {}", s))
+    }
+}
+
+/// Aramada en fazla bu kadar adım atılır, çok dersli ve çok şubeli isteklerde arama sonsuza kadar sürmez
+const MAX_VISITED: u64 = 5_000_000;
+
+/// `plan` sonucu: en yüksek puanlı programlar ve aramanın ne kadarının yapılabildiği
+#[derive(Debug)]
+pub struct Plan<'a> {
+    /// Puana göre sıralı, en iyisi başta
+    pub schedules: Vec<Schedule<'a>>,
+    /// Bulunan tüm çakışmasız programların sayısı
+    pub found: u64,
+    /// Arama sınıra takıldı, denenmemiş kombinasyonlar var
+    pub truncated: bool,
+}
+
+/// Eşit puanlarda önce bulunan önde, böylece sonuç bulunma sırasından bağımsız değişmez
+struct Ranked<'a> {
+    score: i64,
+    order: u64,
+    sections: Vec<&'a Section>,
+}
+
+impl Ranked<'_> {
+    fn key(&self) -> (i64, Reverse<u64>) {
+        (self.score, Reverse(self.order))
+    }
+}
+
+impl PartialEq for Ranked<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Ranked<'_> {}
+
+impl PartialOrd for Ranked<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+struct Search<'a, 'p> {
+    preferences: &'p Preferences,
+    keep: usize,
+    limit: u64,
+    /// En kötüsü en üstte, sadece `keep` kadar program tutulur
+    best: BinaryHeap<Reverse<Ranked<'a>>>,
+    found: u64,
+    visited: u64,
+    truncated: bool,
+}
+
+impl<'a> Search<'a, '_> {
+    fn offer(&mut self, chosen: &[&'a Section]) {
+        let score = self.preferences.score(chosen);
+        let order = self.found;
+        self.found += 1;
+
+        if self.best.len() == self.keep {
+            match self.best.peek() {
+                Some(Reverse(worst)) if (score, Reverse(order)) > worst.key() => {
+                    self.best.pop();
+                }
+                _ => return,
+            }
+        }
+        self.best.push(Reverse(Ranked {
+            score,
+            order,
+            sections: chosen.to_vec(),
+        }));
+    }
+
+    fn run(&mut self, candidates: &[Vec<&'a Section>], chosen: &mut Vec<&'a Section>) {
+        if self.visited >= self.limit {
+            self.truncated = true;
+            return;
+        }
+        self.visited += 1;
+
+        let Some((sections, rest)) = candidates.split_first() else {
+            self.offer(chosen);
+            return;
+        };
+
+        for section in sections {
+            if chosen.iter().any(|c| c.conflicts_with(section)) {
+                continue;
+            }
+
+            chosen.push(section);
+            self.run(rest, chosen);
+            chosen.pop();
+        }
+    }
+}
+
+/// İstenen her dersten bir şube seçerek çakışmasız kombinasyonları dener, puanı en yüksek `keep`
+/// programı döndürür
+pub fn plan<'a>(
+    catalog: &'a Catalog,
+    course_codes: &[String],
+    preferences: &Preferences,
+    keep: usize,
+) -> Result<Plan<'a>, String> {
+    plan_limited(catalog, course_codes, preferences, keep, MAX_VISITED)
+}
+
+fn plan_limited<'a>(
+    catalog: &'a Catalog,
+    course_codes: &[String],
+    preferences: &Preferences,
+    keep: usize,
+    limit: u64,
+) -> Result<Plan<'a>, String> {
+    let mut candidates = Vec::new();
+    for code in course_codes {
+        let sections = catalog.sections_of(code);
+        if sections.is_empty() {
+            return Err(format!("{} dersi için şube bulunamadı", code));
+        }
+        candidates.push(sections);
+    }
+
+    let mut search = Search {
+        preferences,
+        keep,
+        limit,
+        best: BinaryHeap::with_capacity(keep + 1),
+        found: 0,
+        visited: 0,
+        truncated: false,
+    };
+    search.run(&candidates, &mut Vec::new());
+
+    Ok(Plan {
+        schedules: search
+            .best
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| Schedule {
+                sections: ranked.sections,
+                score: ranked.score,
+            })
+            .collect(),
+        found: search.found,
+        truncated: search.truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Session;
+
+    fn section(crn: &str, course: &str, instructor: &str, sessions: &[(Weekday, u32)]) -> Section {
+        Section {
+            crn: crn.to_string(),
+            course_code: course.to_string(),
+            course_title: String::new(),
+            instructor: instructor.to_string(),
+            sessions: sessions
+                .iter()
+                .map(|(day, hour)| Session {
+                    day: *day,
+                    start: NaiveTime::from_hms_opt(*hour, 30, 0).unwrap(),
+                    end: NaiveTime::from_hms_opt(*hour + 2, 29, 0).unwrap(),
+                })
+                .collect(),
+            capacity: 50,
+            enrolled: 10,
+        }
+    }
+
+    fn catalog() -> Catalog {
+        Catalog {
+            sections: vec![
+                section("100", "MAT 103", "Ali Veli", &[(Weekday::Mon, 8)]),
+                section("101", "MAT 103", "Ayşe Yılmaz", &[(Weekday::Tue, 13)]),
+                section("200", "FIZ 101", "Can Demir", &[(Weekday::Mon, 9)]),
+                section("201", "FIZ 101", "Deniz Kaya", &[(Weekday::Fri, 8)]),
+            ],
+        }
+    }
+
+    fn codes(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|c| c.to_string()).collect()
+    }
+
+    fn crn_lists(schedules: &[Schedule]) -> Vec<Vec<String>> {
+        schedules.iter().map(|s| s.crn_list()).collect()
+    }
+
+    #[test]
+    fn skips_conflicting_combinations() {
+        let catalog = catalog();
+        let schedules = plan(
+            &catalog,
+            &codes(&["MAT 103", "FIZ 101"]),
+            &Preferences::default(),
+            10,
+        )
+        .unwrap()
+        .schedules;
+
+        // 100 ve 200 pazartesi sabah çakışıyor
+        let mut crns = crn_lists(&schedules);
+        crns.sort();
+        assert_eq!(
+            crns,
+            vec![
+                codes(&["100", "201"]),
+                codes(&["101", "200"]),
+                codes(&["101", "201"]),
+            ]
+        );
+        assert!(schedules.iter().all(|s| s.score == 0));
+    }
+
+    #[test]
+    fn unknown_course_is_an_error() {
+        let catalog = catalog();
+        let err = plan(&catalog, &codes(&["BLG 102E"]), &Preferences::default(), 10).unwrap_err();
+        assert!(err.contains("BLG 102E"), "{}", err);
+    }
+
+    #[test]
+    fn no_schedule_when_everything_conflicts() {
+        let catalog = Catalog {
+            sections: vec![
+                section("100", "MAT 103", "Ali Veli", &[(Weekday::Mon, 8)]),
+                section("200", "FIZ 101", "Can Demir", &[(Weekday::Mon, 9)]),
+            ],
+        };
+        let schedules = plan(
+            &catalog,
+            &codes(&["MAT 103", "FIZ 101"]),
+            &Preferences::default(),
+            10,
+        )
+        .unwrap()
+        .schedules;
+        assert!(schedules.is_empty());
+    }
+
+    #[test]
+    fn scores_preferences() {
+        let catalog = catalog();
+        let preferences = Preferences {
+            not_before: NaiveTime::from_hms_opt(9, 0, 0),
+            free_days: vec![Weekday::Fri],
+            preferred_instructors: vec!["yılmaz".to_string()],
+        };
+        let schedules = plan(&catalog, &codes(&["MAT 103", "FIZ 101"]), &preferences, 10)
+            .unwrap()
+            .schedules;
+
+        let scores: Vec<(Vec<String>, i64)> =
+            schedules.iter().map(|s| (s.crn_list(), s.score)).collect();
+        assert_eq!(
+            scores,
+            vec![
+                // Tercih edilen eğitmen, erken ders yok
+                (codes(&["101", "200"]), 3),
+                // Tercih edilen eğitmen, cuma 08:30 hem erken hem boş istenen gün
+                (codes(&["101", "201"]), 3 - 10 - 20),
+                // İki erken ders ve cuma
+                (codes(&["100", "201"]), -10 - 10 - 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn full_sections_are_penalized() {
+        let mut catalog = catalog();
+        catalog.sections[1].enrolled = catalog.sections[1].capacity;
+        let schedules = plan(&catalog, &codes(&["MAT 103"]), &Preferences::default(), 10)
+            .unwrap()
+            .schedules;
+
+        assert_eq!(
+            crn_lists(&schedules),
+            vec![codes(&["100"]), codes(&["101"])]
+        );
+        assert_eq!(schedules[1].score, -5);
+    }
+
+    #[test]
+    fn keeps_only_the_best() {
+        let catalog = catalog();
+        let preferences = Preferences {
+            not_before: NaiveTime::from_hms_opt(9, 0, 0),
+            free_days: vec![Weekday::Fri],
+            preferred_instructors: vec!["yılmaz".to_string()],
+        };
+        let plan = plan(&catalog, &codes(&["MAT 103", "FIZ 101"]), &preferences, 2).unwrap();
+
+        assert_eq!(plan.found, 3);
+        assert!(!plan.truncated);
+        assert_eq!(
+            crn_lists(&plan.schedules),
+            vec![codes(&["101", "200"]), codes(&["101", "201"])]
+        );
+    }
+
+    #[test]
+    fn equal_scores_keep_the_search_order() {
+        let catalog = catalog();
+        let plan = plan(
+            &catalog,
+            &codes(&["MAT 103", "FIZ 101"]),
+            &Preferences::default(),
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(
+            crn_lists(&plan.schedules),
+            vec![codes(&["100", "201"]), codes(&["101", "200"])]
+        );
+    }
+
+    #[test]
+    fn reports_when_the_search_is_cut_short() {
+        let catalog = catalog();
+        let plan = plan_limited(
+            &catalog,
+            &codes(&["MAT 103", "FIZ 101"]),
+            &Preferences::default(),
+            10,
+            3,
+        )
+        .unwrap();
+
+        assert!(plan.truncated);
+        assert!(plan.found < 3);
+    }
+}
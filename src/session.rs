@@ -0,0 +1,282 @@
+use std::{
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, TimeDelta, Utc};
+use cookie_store::CookieStore;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Başarılı bir girişten sonra kaydedilen çerezler ve API Token
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedSession {
+    pub jwt: String,
+    pub saved_at: DateTime<Utc>,
+    cookies: Value,
+}
+
+impl CachedSession {
+    /// Token'ın süresi dolmak üzereyse oturum kullanılmaz
+    const EXPIRY_MARGIN: TimeDelta = TimeDelta::minutes(2);
+    /// Token'dan süre okunamazsa oturum bu kadar süre geçerli sayılır
+    const FALLBACK_LIFETIME: TimeDelta = TimeDelta::minutes(30);
+
+    pub fn new(cookies: &CookieStore, jwt: &str) -> Result<Self, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        cookie_store::serde::json::save_incl_expired_and_nonpersistent(cookies, &mut buffer)
+            .map_err(|e| e as Box<dyn Error>)?;
+
+        Ok(Self {
+            jwt: jwt.to_string(),
+            saved_at: Utc::now(),
+            cookies: serde_json::from_slice(&buffer)?,
+        })
+    }
+
+    pub fn cookie_store(&self) -> Result<CookieStore, Box<dyn Error>> {
+        let buffer = serde_json::to_vec(&self.cookies)?;
+        cookie_store::serde::json::load_all(BufReader::new(buffer.as_slice()))
+            .map_err(|e| e as Box<dyn Error>)
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        jwt_expiry(&self.jwt).unwrap_or(self.saved_at + Self::FALLBACK_LIFETIME)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        Utc::now() + Self::EXPIRY_MARGIN < self.expires_at()
+    }
+}
+
+/// Token'ın `exp` alanını okur, token imzası doğrulanmaz
+pub fn jwt_expiry(jwt: &str) -> Option<DateTime<Utc>> {
+    let payload = jwt.trim().trim_matches('"').split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: Value = serde_json::from_slice(&payload).ok()?;
+
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+/// FNV-1a, Rust sürümünden bağımsız olarak aynı kalır
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Kullanıcı adından klasörün dışına çıkamayan bir dosya adı üretir. Sadece harf, rakam, ".", "_"
+/// ve "-" kalır; ad değiştiyse ya da boş kaldıysa farklı adlar aynı dosyaya düşmesin diye özet eklenir.
+pub fn file_stem(username: &str) -> String {
+    let safe: String = username
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let safe = safe.trim_start_matches('.');
+
+    if !safe.is_empty() && safe == username {
+        return safe.to_string();
+    }
+    format!("{}-{:08x}", safe, fnv1a(username.as_bytes()) as u32)
+}
+
+/// Her kullanıcı için ayrı, sadece kullanıcının okuyabildiği bir oturum dosyası
+#[derive(Debug, Clone)]
+pub struct SessionCache {
+    path: PathBuf,
+}
+
+impl SessionCache {
+    pub fn for_user(username: &str) -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("itu-keplerbot");
+
+        Self {
+            path: dir.join(format!("{}.json", file_stem(username))),
+        }
+    }
+
+    pub fn load(&self) -> Result<Option<CachedSession>, Box<dyn Error>> {
+        match File::open(&self.path) {
+            Ok(file) => Ok(Some(serde_json::from_reader(file)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    pub fn save(&self, session: &CachedSession) -> Result<(), Box<dyn Error>> {
+        write_private_file(&self.path, &serde_json::to_vec(session)?)
+    }
+}
+
+/// Var olan bir dosyayı sadece kullanıcının okuyup yazabileceği hale getirir
+pub fn make_private(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Dosyayı sadece kullanıcının okuyabileceği şekilde yazar, gerekirse klasörü oluşturur
+pub fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    // mode sadece dosya ilk oluşturulurken uygulanır
+    make_private(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Giriş sayfası değiştiğinde tarayıcıdan alınan oturumla devam etmek için
+#[derive(Debug, Default)]
+pub struct ImportedSession {
+    pub cookies: Option<CookieStore>,
+    pub jwt: Option<String>,
+}
+
+impl ImportedSession {
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_none() && self.jwt.is_none()
+    }
+}
+
+/// Netscape biçimindeki cookies.txt dosyasını okur. Süresi dolmuş çerezler atlanır.
+pub fn load_netscape_cookies(path: &Path) -> Result<CookieStore, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let mut store = CookieStore::default();
+    let now = Utc::now().timestamp();
+
+    for (line_number, line) in content.lines().enumerate() {
+        // "#HttpOnly_" öneki yorum değil, HttpOnly çerezleri işaretler
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [domain, include_subdomains, cookie_path, secure, expires, name, value] = fields[..]
+        else {
+            return Err(format!(
+                "{} dosyasının {}. satırı cookies.txt biçiminde değil",
+                path.display(),
+                line_number + 1
+            )
+            .into());
+        };
+
+        let expires: i64 = expires.parse()?;
+        let mut set_cookie = format!("{}={}; Path={}", name, value, cookie_path);
+        if include_subdomains == "TRUE" {
+            set_cookie += &format!("; Domain={}", domain);
+        }
+        if secure == "TRUE" {
+            set_cookie += "; Secure";
+        }
+        if http_only {
+            set_cookie += "; HttpOnly";
+        }
+        // 0 oturum çerezi demek
+        if expires != 0 {
+            if expires <= now {
+                continue;
+            }
+            set_cookie += &format!("; Max-Age={}", expires - now);
+        }
+
+        let url = Url::parse(&format!(
+            "https://{}{}",
+            domain.trim_start_matches('.'),
+            cookie_path
+        ))?;
+        store.parse(&set_cookie, &url)?;
+    }
+
+    Ok(store)
+}
+
+/// Token'ı dosyadan, dosya adı "-" ise standart girdiden okur
+pub fn read_jwt(path: &Path) -> Result<String, Box<dyn Error>> {
+    let jwt = if path == Path::new("-") {
+        let mut jwt = String::new();
+        io::stdin().read_to_string(&mut jwt)?;
+        jwt
+    } else {
+        fs::read_to_string(path)?
+    };
+
+    let jwt = jwt.trim().to_string();
+    if jwt.is_empty() {
+        return Err(format!("{} içinde API Token yok", path.display()).into());
+    }
+
+    Ok(jwt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_usernames_are_kept() {
+        assert_eq!(file_stem("yilmaza20"), "yilmaza20");
+        assert_eq!(file_stem("ali.veli_2-b"), "ali.veli_2-b");
+    }
+
+    #[test]
+    fn usernames_cannot_leave_the_directory() {
+        for username in [
+            "../../etc/passwd",
+            "..",
+            "",
+            "a/b",
+            "a\\b",
+            ".gizli",
+            "öğrenci",
+        ] {
+            let stem = file_stem(username);
+            assert!(!stem.is_empty(), "{:?}", username);
+            assert!(!stem.starts_with('.'), "{:?} -> {}", username, stem);
+            assert!(
+                stem.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')),
+                "{:?} -> {}",
+                username,
+                stem
+            );
+        }
+    }
+
+    #[test]
+    fn replaced_names_do_not_collide() {
+        assert_ne!(file_stem("a/b"), file_stem("a_b"));
+        assert_ne!(file_stem("a/b"), file_stem("a\\b"));
+        assert_ne!(file_stem(""), file_stem(".."));
+    }
+}
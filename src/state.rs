@@ -0,0 +1,213 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{self, File},
+    io::ErrorKind,
+    path::PathBuf,
+};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attempt::AttemptOutcome,
+    course::RegistrationReport,
+    requester::Job,
+    session::{self, CachedSession},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrnAction {
+    Add,
+    Drop,
+}
+
+impl CrnAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrnAction::Add => "add",
+            CrnAction::Drop => "drop",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "add" => Some(CrnAction::Add),
+            "drop" => Some(CrnAction::Drop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrnState {
+    pub action: CrnAction,
+    pub last_code: Option<String>,
+    /// Başarılı oldu ya da tekrar denemenin anlamı yok
+    pub settled: bool,
+}
+
+/// Bir pencerenin her denemeden sonra diske yazılan durumu. Program yarıda kapanırsa
+/// aynı config ile yeniden çalıştırıldığında kalınan yerden devam edilir.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunState {
+    pub opening: DateTime<FixedOffset>,
+    pub attempts: u64,
+    /// Denemeler normal şekilde bitti, yeniden çalıştırılırsa deneme sayısı sıfırdan başlar
+    pub finished: bool,
+    pub crns: BTreeMap<String, CrnState>,
+    pub session: Option<CachedSession>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RunState {
+    pub fn new(job: &Job) -> Self {
+        let adds = job.crn_list.iter().map(|crn| (crn, CrnAction::Add));
+        let drops = job.scrn_list.iter().map(|crn| (crn, CrnAction::Drop));
+
+        Self {
+            opening: job.time,
+            attempts: 0,
+            finished: false,
+            crns: adds
+                .chain(drops)
+                .map(|(crn, action)| {
+                    (
+                        crn.clone(),
+                        CrnState {
+                            action,
+                            last_code: None,
+                            settled: false,
+                        },
+                    )
+                })
+                .collect(),
+            session: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Önceki durumdan, CRN'i ve işlemi değişmemiş olanların sonuçları alınır
+    pub fn resume(previous: RunState, job: &Job) -> Self {
+        let mut state = Self::new(job);
+        if !previous.finished {
+            state.attempts = previous.attempts;
+        }
+        state.session = previous.session;
+
+        for (crn, crn_state) in state.crns.iter_mut() {
+            if let Some(old) = previous.crns.get(crn) {
+                if old.action == crn_state.action {
+                    *crn_state = old.clone();
+                }
+            }
+        }
+        state
+    }
+
+    pub fn record(&mut self, outcome: &AttemptOutcome) {
+        self.attempts += 1;
+        self.updated_at = Utc::now();
+
+        let Some(body) = outcome.response() else {
+            return;
+        };
+        let results = body
+            .add_results()
+            .into_iter()
+            .map(|r| (CrnAction::Add, r))
+            .chain(
+                body.drop_results()
+                    .into_iter()
+                    .map(|r| (CrnAction::Drop, r)),
+            );
+        for (action, (crn, code, settled)) in results {
+            if let Some(crn_state) = self.crns.get_mut(&crn) {
+                if crn_state.action == action {
+                    crn_state.last_code = Some(code);
+                    crn_state.settled |= settled;
+                }
+            }
+        }
+    }
+
+    pub fn settled(&self) -> Vec<&str> {
+        self.crns
+            .iter()
+            .filter(|(_, s)| s.settled)
+            .map(|(crn, _)| crn.as_str())
+            .collect()
+    }
+
+    /// Kayıtlı dersler alınamadığında son durum: sonuçlanmış CRN'ler gerçekleşmiş sayılır
+    pub fn report(&self, job: &Job) -> RegistrationReport {
+        let settled = |crn: &String| (crn.clone(), self.crns.get(crn).is_some_and(|s| s.settled));
+
+        RegistrationReport::from_attempts(
+            job.crn_list.iter().map(settled).collect(),
+            job.scrn_list.iter().map(settled).collect(),
+        )
+    }
+
+    /// Sadece henüz sonuçlanmamış CRN'lerle pencere
+    pub fn pending_job(&self, job: &Job) -> Job {
+        let pending = |crn: &String| !self.crns.get(crn).is_some_and(|s| s.settled);
+
+        Job {
+            name: job.name.clone(),
+            time: job.time,
+            crn_list: job
+                .crn_list
+                .iter()
+                .filter(|c| pending(c))
+                .cloned()
+                .collect(),
+            scrn_list: job
+                .scrn_list
+                .iter()
+                .filter(|c| pending(c))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Her kullanıcı ve pencere için ayrı durum dosyası
+#[derive(Debug, Clone)]
+pub struct StateJournal {
+    path: PathBuf,
+}
+
+impl StateJournal {
+    pub fn for_job(username: &str, job: &Job) -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("itu-keplerbot")
+            .join("state");
+
+        Self {
+            path: dir.join(format!(
+                "{}-{}.json",
+                session::file_stem(username),
+                job.time.timestamp()
+            )),
+        }
+    }
+
+    pub fn load(&self) -> Result<Option<RunState>, Box<dyn Error>> {
+        match File::open(&self.path) {
+            Ok(file) => Ok(Some(serde_json::from_reader(file)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    /// Önce geçici dosyaya yazılıp taşınır, yazarken kapanırsa eski durum bozulmaz
+    pub fn save(&self, state: &RunState) -> Result<(), Box<dyn Error>> {
+        let temp_path = self.path.with_extension("json.tmp");
+        session::write_private_file(&temp_path, &serde_json::to_vec(state)?)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeDelta, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+use crate::requester::Config;
+
+const OFFSET_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%:z",
+    "%Y-%m-%d %H:%M%:z",
+    "%Y-%m-%dT%H:%M%:z",
+    "%Y-%m-%d %H:%M:%S %:z",
+    "%Y-%m-%d %H:%M %:z",
+];
+
+/// Girdi küçük harfe çevrildikten sonra denenir, bu yüzden ayraç "t"
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dt%H:%M:%S",
+    "%Y-%m-%dt%H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d %m %Y %H:%M:%S",
+    "%d %m %Y %H:%M",
+];
+
+const SLASH_FORMATS: &[&str] = &["%d/%m/%Y %H:%M:%S", "%d/%m/%Y %H:%M"];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%d %m %Y"];
+
+const TURKISH_MONTHS: &[&str] = &[
+    "ocak", "şubat", "mart", "nisan", "mayıs", "haziran", "temmuz", "ağustos", "eylül", "ekim",
+    "kasım", "aralık",
+];
+
+const TURKISH_UNITS: &[(&str, &str)] = &[
+    ("saniye", "s"),
+    ("dakika", "m"),
+    ("saat", "h"),
+    ("gün", "d"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Trt,
+    Named(Tz),
+}
+
+impl Zone {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "TRT" | "trt" => Some(Zone::Trt),
+            _ => name.parse().ok().map(Zone::Named),
+        }
+    }
+
+    fn today(&self, now: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Zone::Trt => now.with_timezone(&Config::TRT_TIMEZONE).date_naive(),
+            Zone::Named(tz) => now.with_timezone(tz).date_naive(),
+        }
+    }
+
+    fn resolve(&self, naive: NaiveDateTime) -> Result<DateTime<FixedOffset>, String> {
+        let resolved = match self {
+            Zone::Trt => Config::TRT_TIMEZONE.from_local_datetime(&naive),
+            Zone::Named(tz) => tz.from_local_datetime(&naive).map(|t| t.fixed_offset()),
+        };
+
+        match resolved {
+            LocalResult::Single(time) => Ok(time.with_timezone(&Config::TRT_TIMEZONE)),
+            LocalResult::Ambiguous(a, b) => Err(format!(
+                "{} bu saat diliminde iki kere yaşanıyor ({} / {}), lütfen UTC farkını açıkça yazın",
+                naive, a, b
+            )),
+            LocalResult::None => Err(format!("{} bu saat diliminde hiç yaşanmıyor", naive)),
+        }
+    }
+}
+
+fn parse_relative(input: &str) -> Option<Result<TimeDelta, String>> {
+    let rest = input
+        .strip_prefix("in ")
+        .or_else(|| input.strip_suffix(" sonra"))
+        .or_else(|| input.strip_prefix('+'))?;
+
+    let rest = TURKISH_UNITS
+        .iter()
+        .fold(rest.to_string(), |acc, (tr, unit)| acc.replace(tr, unit));
+    let rest = rest.replace(' ', "");
+
+    Some(
+        humantime::parse_duration(&rest)
+            .map_err(|e| format!("{} süresi anlaşılamadı ({})", input, e))
+            .and_then(|d| TimeDelta::from_std(d).map_err(|e| e.to_string())),
+    )
+}
+
+fn parse_day_keyword(
+    input: &str,
+    zone: Zone,
+    now: DateTime<Utc>,
+) -> Option<Result<NaiveDateTime, String>> {
+    let (day, time) = input.split_once(' ')?;
+    let offset_days = match dotless(day).as_str() {
+        "bugün" | "today" => 0,
+        "yarin" | "tomorrow" => 1,
+        _ => return None,
+    };
+
+    let date = zone.today(now) + TimeDelta::days(offset_days);
+    Some(
+        parse_clock(time)
+            .map(|time| date.and_time(time))
+            .ok_or_else(|| format!("{} saati anlaşılamadı, SS:DD biçiminde yazın", time)),
+    )
+}
+
+fn parse_clock(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+        .ok()
+}
+
+/// `to_lowercase` "İ" harfini "i" ve ayrı bir nokta (U+0307) olarak yazar, nokta silinir
+fn turkish_lowercase(input: &str) -> String {
+    input.to_lowercase().replace('\u{307}', "")
+}
+
+/// `to_lowercase` "I" harfini "ı" değil "i" yapar, karşılaştırmalar "ı" harfi "i" sayılarak yapılır
+fn dotless(word: &str) -> String {
+    word.replace('ı', "i")
+}
+
+/// "10 Şubat 2025" gibi ay adlarını sayıya çevirir
+fn replace_month_names(input: &str) -> String {
+    input
+        .split(' ')
+        .map(|word| {
+            match TURKISH_MONTHS
+                .iter()
+                .position(|m| dotless(m) == dotless(word))
+            {
+                Some(i) => format!("{:02}", i + 1),
+                None => word.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_naive(input: &str) -> Result<NaiveDateTime, String> {
+    let input = replace_month_names(input);
+
+    if let Some(naive) = NAIVE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(&input, f).ok())
+    {
+        return Ok(naive);
+    }
+
+    if let Some(naive) = SLASH_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(&input, f).ok())
+    {
+        if naive.day() <= 12 && naive.day() != naive.month() {
+            return Err(format!(
+                "{} belirsiz: gün/ay mı ay/gün mü anlaşılamıyor. GG.AA.YYYY veya YYYY-AA-GG biçimini kullanın",
+                input
+            ));
+        }
+        return Ok(naive);
+    }
+
+    if DATE_FORMATS
+        .iter()
+        .any(|f| NaiveDate::parse_from_str(&input, f).is_ok())
+    {
+        return Err(format!("{} için saat belirtilmemiş", input));
+    }
+
+    if parse_clock(&input).is_some() {
+        return Err(format!(
+            "{} için gün belirtilmemiş. \"bugün {}\" veya \"yarın {}\" yazabilirsiniz",
+            input, input, input
+        ));
+    }
+
+    Err(format!(
+        "{} zamanı anlaşılamadı. Örnek: \"2025-02-10 14:00:00\", \"10.02.2025 14:00\", \"yarın 14:00\", \"in 10m\"",
+        input
+    ))
+}
+
+/// Ders seçim zamanını ayrıştırır. Saat dilimi belirtilmemişse TRT varsayılır.
+pub fn parse(input: &str, now: DateTime<Utc>) -> Result<DateTime<FixedOffset>, String> {
+    let input = input.trim();
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Config::TRT_TIMEZONE));
+    }
+    if let Some(time) = OFFSET_FORMATS
+        .iter()
+        .find_map(|f| DateTime::parse_from_str(input, f).ok())
+    {
+        return Ok(time.with_timezone(&Config::TRT_TIMEZONE));
+    }
+
+    let lowercase = turkish_lowercase(input);
+    if let Some(delta) = parse_relative(&lowercase) {
+        return Ok((now + delta?).with_timezone(&Config::TRT_TIMEZONE));
+    }
+
+    let (text, zone) = match input
+        .rsplit_once(' ')
+        .and_then(|(text, name)| Zone::parse(name).map(|zone| (turkish_lowercase(text), zone)))
+    {
+        Some((text, zone)) => (text, zone),
+        None => (lowercase, Zone::Trt),
+    };
+
+    let naive = match parse_day_keyword(&text, zone, now) {
+        Some(naive) => naive?,
+        None => parse_naive(&text)?,
+    };
+
+    zone.resolve(naive)
+}
+
+/// Zamanı hem TRT hem de yerel saat olarak yazar
+pub fn describe(time: &DateTime<FixedOffset>) -> String {
+    format!(
+        "{} TRT (yerel saat: {})",
+        time.with_timezone(&Config::TRT_TIMEZONE)
+            .format("%Y-%m-%d %H:%M:%S"),
+        time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-02-10 10:00 UTC, TRT'de 13:00
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 2, 10, 10, 0, 0).unwrap()
+    }
+
+    fn trt(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<FixedOffset> {
+        Config::TRT_TIMEZONE
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+    }
+
+    fn parsed(input: &str) -> DateTime<FixedOffset> {
+        parse(input, now()).unwrap_or_else(|e| panic!("{}: {}", input, e))
+    }
+
+    #[test]
+    fn numeric_formats_default_to_trt() {
+        for input in [
+            "2025-02-10 14:00:00",
+            "2025-02-10 14:00",
+            "2025-02-10T14:00",
+            "10.02.2025 14:00",
+            "  10.02.2025 14:00:00  ",
+        ] {
+            let time = parsed(input);
+            assert_eq!(time, trt(2025, 2, 10, 14, 0), "{}", input);
+            assert_eq!(time.offset(), &Config::TRT_TIMEZONE, "{}", input);
+        }
+    }
+
+    #[test]
+    fn turkish_month_names() {
+        assert_eq!(parsed("10 Şubat 2025 14:00"), trt(2025, 2, 10, 14, 0));
+        assert_eq!(parsed("1 ocak 2026 09:30"), trt(2026, 1, 1, 9, 30));
+        assert_eq!(parsed("31 ARALIK 2025 23:59"), trt(2025, 12, 31, 23, 59));
+        assert_eq!(parsed("5 Mayıs 2025 10:00"), trt(2025, 5, 5, 10, 0));
+        assert_eq!(parsed("10 NİSAN 2025 10:00"), trt(2025, 4, 10, 10, 0));
+        assert_eq!(parsed("1 EKİM 2025 09:00"), trt(2025, 10, 1, 9, 0));
+        assert_eq!(parsed("15 HAZİRAN 2025 14:00"), trt(2025, 6, 15, 14, 0));
+        assert_eq!(parsed("20 EYLÜL 2025 08:30 TRT"), trt(2025, 9, 20, 8, 30));
+    }
+
+    #[test]
+    fn relative_forms() {
+        let now = now();
+        assert_eq!(parsed("in 10m"), now + TimeDelta::minutes(10));
+        assert_eq!(parsed("+1h 30m"), now + TimeDelta::minutes(90));
+        assert_eq!(parsed("10 dakika sonra"), now + TimeDelta::minutes(10));
+        assert_eq!(parsed("2 saat sonra"), now + TimeDelta::hours(2));
+        assert!(parse("in birkaç dakika", now).is_err());
+    }
+
+    #[test]
+    fn day_keywords_use_the_zone_date() {
+        assert_eq!(parsed("bugün 14:00"), trt(2025, 2, 10, 14, 0));
+        assert_eq!(parsed("Yarın 14:00"), trt(2025, 2, 11, 14, 0));
+        assert_eq!(parsed("YARIN 14:00"), trt(2025, 2, 11, 14, 0));
+        assert_eq!(parsed("BUGÜN 14:00"), trt(2025, 2, 10, 14, 0));
+        assert_eq!(parsed("İN 10M"), now() + TimeDelta::minutes(10));
+        assert_eq!(parsed("tomorrow 09:15:00"), trt(2025, 2, 11, 9, 15));
+
+        // UTC'de hâlâ 10 Şubat ama TRT'de 11 Şubat
+        let late = Utc.with_ymd_and_hms(2025, 2, 10, 22, 0, 0).unwrap();
+        assert_eq!(parse("yarın 14:00", late).unwrap(), trt(2025, 2, 12, 14, 0));
+        assert!(parse("yarın öğlen", now()).is_err());
+    }
+
+    #[test]
+    fn explicit_offsets_and_zones() {
+        let expected = trt(2025, 2, 10, 16, 0);
+        assert_eq!(parsed("2025-02-10T14:00:00+01:00"), expected);
+        assert_eq!(parsed("2025-02-10 14:00+01:00"), expected);
+        assert_eq!(parsed("2025-02-10 14:00 +01:00"), expected);
+        assert_eq!(parsed("2025-02-10 14:00 Europe/Berlin"), expected);
+        assert_eq!(parsed("2025-02-10 13:00 UTC"), expected);
+        assert_eq!(parsed("2025-02-10 16:00 TRT"), expected);
+        assert_eq!(
+            parsed("2025-02-10 14:00+01:00").offset(),
+            &Config::TRT_TIMEZONE
+        );
+    }
+
+    #[test]
+    fn ambiguous_slash_dates_are_rejected() {
+        let err = parse("10/02/2025 14:00", now()).unwrap_err();
+        assert!(err.contains("belirsiz"), "{}", err);
+
+        // Gün 12'den büyükse ya da gün ve ay aynıysa tek anlamı var
+        assert_eq!(parsed("13/02/2025 14:00"), trt(2025, 2, 13, 14, 0));
+        assert_eq!(parsed("02/02/2025 14:00"), trt(2025, 2, 2, 14, 0));
+    }
+
+    #[test]
+    fn missing_parts_are_reported() {
+        let err = parse("2025-02-10", now()).unwrap_err();
+        assert!(err.contains("saat belirtilmemiş"), "{}", err);
+
+        let err = parse("14:00", now()).unwrap_err();
+        assert!(err.contains("gün belirtilmemiş"), "{}", err);
+
+        let err = parse("pazartesi sabah", now()).unwrap_err();
+        assert!(err.contains("anlaşılamadı"), "{}", err);
+    }
+
+    #[test]
+    fn clock_change_gaps_and_repeats() {
+        // Yaz saatine geçişte 02:00-03:00 yaşanmaz
+        let err = parse("2025-03-30 02:30 Europe/Berlin", now()).unwrap_err();
+        assert!(err.contains("hiç yaşanmıyor"), "{}", err);
+
+        // Kış saatine dönüşte 02:00-03:00 iki kere yaşanır
+        let err = parse("2025-10-26 02:30 Europe/Berlin", now()).unwrap_err();
+        assert!(err.contains("iki kere"), "{}", err);
+
+        // UTC farkı açıkça yazılınca aynı saat kabul edilir
+        assert_eq!(parsed("2025-10-26 02:30+01:00"), trt(2025, 10, 26, 4, 30));
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::net::lookup_host;
+
+/// Sabitlenmiş adları önceden çözülmüş adreslere, diğerlerini sistem çözümleyicisine yönlendirir.
+/// Her yeni bağlantı için ad çözümlemesi yapıldığından, sayaç bir isteğin sıcak bir bağlantıyı
+/// kullanıp kullanmadığını anlamaya da yarar.
+#[derive(Debug, Default)]
+pub struct PinnedResolver {
+    pinned: Mutex<HashMap<String, Vec<SocketAddr>>>,
+    resolutions: AtomicU64,
+}
+
+impl PinnedResolver {
+    /// Adı şimdi çözer ve sonraki tüm bağlantılarda bu adresleri kullanır
+    pub async fn pin(&self, host: &str) -> Result<Vec<SocketAddr>, Box<dyn Error>> {
+        // port 0 bırakılırsa bağlantı kurulurken URL'deki port kullanılır
+        let addrs: Vec<SocketAddr> = lookup_host((host, 0)).await?.collect();
+        if addrs.is_empty() {
+            return Err(format!("{} için adres bulunamadı", host).into());
+        }
+
+        self.pinned
+            .lock()
+            .expect("pinned addresses lock")
+            .insert(host.to_string(), addrs.clone());
+        Ok(addrs)
+    }
+
+    /// Şimdiye kadar kaç yeni bağlantı için ad çözümlemesi yapıldığı
+    pub fn resolutions(&self) -> u64 {
+        self.resolutions.load(Ordering::SeqCst)
+    }
+}
+
+impl Resolve for PinnedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        self.resolutions.fetch_add(1, Ordering::SeqCst);
+
+        let pinned = self
+            .pinned
+            .lock()
+            .expect("pinned addresses lock")
+            .get(name.as_str())
+            .cloned();
+
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = match pinned {
+                Some(addrs) => addrs,
+                None => lookup_host((name.as_str(), 0)).await?.collect(),
+            };
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+use crate::{
+    catalog::Catalog,
+    cli::{self, MakeConfigArgs},
+    requester::Config,
+    time_input,
+};
+
+fn prompt(label: &str) -> io::Result<String> {
+    print!("{}: ", label);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(answer.trim().to_string())
+}
+
+fn prompt_non_empty(label: &str) -> io::Result<String> {
+    loop {
+        let answer = prompt(label)?;
+        if !answer.is_empty() {
+            return Ok(answer);
+        }
+        println!("Bu alan boş bırakılamaz.");
+    }
+}
+
+fn prompt_crn_list(label: &str) -> io::Result<Vec<String>> {
+    let answer = prompt(label)?;
+    Ok(answer
+        .split([',', ' '])
+        .filter(|crn| !crn.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+fn prompt_time() -> io::Result<chrono::DateTime<chrono::FixedOffset>> {
+    loop {
+        let answer = prompt_non_empty("Ders seçim zamanı (örn. 2025-02-10 14:00, yarın 14:00)")?;
+
+        let time = match cli::parse_time(&answer) {
+            Ok(time) => time,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+
+        println!("{}", time_input::describe(&time));
+        if cli::confirm("Bu zaman doğru mu?")? {
+            return Ok(time);
+        }
+    }
+}
+
+async fn load_catalog(args: &MakeConfigArgs) -> Option<Catalog> {
+    if let Some(catalog_path) = &args.catalog_path {
+        return match Catalog::from_file(catalog_path) {
+            Ok(catalog) => Some(catalog),
+            Err(e) => {
+                println!(
+                    "Ders programı okunamadı, CRN'ler kontrol edilmeyecek: {}",
+                    e
+                );
+                None
+            }
+        };
+    }
+
+    let branches =
+        prompt("CRN'leri kontrol etmek için branş kodları (örn. BLG, MAT; boş bırakılabilir)")
+            .ok()?
+            .split([',', ' '])
+            .filter(|b| !b.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+    if branches.is_empty() {
+        return None;
+    }
+
+    println!("Ders programı OBS'den çekiliyor...");
+    match Catalog::fetch(&reqwest::Client::new(), &branches).await {
+        Ok(catalog) => Some(catalog),
+        Err(e) => {
+            println!(
+                "Ders programı çekilemedi, CRN'ler kontrol edilmeyecek: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Katalog varsa her CRN'i gösterir, bulunamayanlar için onay ister
+fn check_crn_list(catalog: Option<&Catalog>, crn_list: Vec<String>) -> io::Result<Vec<String>> {
+    let Some(catalog) = catalog else {
+        return Ok(crn_list);
+    };
+
+    let mut checked = Vec::new();
+    for crn in crn_list {
+        match catalog.find_crn(&crn) {
+            Some(section) => {
+                println!("{}", section);
+                checked.push(crn);
+            }
+            None => {
+                if cli::confirm(&format!(
+                    "CRN {} ders programında yok. Yine de eklensin mi?",
+                    crn
+                ))? {
+                    checked.push(crn);
+                }
+            }
+        }
+    }
+
+    Ok(checked)
+}
+
+/// Bırakılacak her CRN için ayrı ayrı onay ister
+fn confirm_drops(catalog: Option<&Catalog>, scrn_list: Vec<String>) -> io::Result<Vec<String>> {
+    let mut confirmed = Vec::new();
+    for crn in scrn_list {
+        if let Some(section) = catalog.and_then(|c| c.find_crn(&crn)) {
+            println!("{}", section);
+        }
+
+        if cli::confirm(&format!("CRN {} BIRAKILACAK. Emin misin?", crn))? {
+            confirmed.push(crn);
+        }
+    }
+
+    Ok(confirmed)
+}
+
+/// `make-config --interactive` için bilgileri adım adım sorar, komut satırında verilenleri tekrar sormaz
+pub async fn run(args: MakeConfigArgs) -> Result<Config, Box<dyn Error>> {
+    let username = match &args.username {
+        Some(username) => username.clone(),
+        None => prompt_non_empty("ITÜ kullanıcı adı")?,
+    };
+
+    let password = match &args.password {
+        Some(password) => password.clone(),
+        None => rpassword::prompt_password("ITÜ şifresi: ")?,
+    };
+
+    let time = match args.time {
+        Some(time) => time,
+        None => prompt_time()?,
+    };
+
+    let catalog = load_catalog(&args).await;
+
+    let crn_list = if args.crn_list.is_empty() {
+        prompt_crn_list("Eklenecek CRN'ler (virgülle ayırın)")?
+    } else {
+        args.crn_list.clone()
+    };
+    let crn_list = check_crn_list(catalog.as_ref(), crn_list)?;
+
+    let scrn_list = if args.scrn_list.is_empty() {
+        prompt_crn_list("Bırakılacak CRN'ler (virgülle ayırın, boş bırakılabilir)")?
+    } else {
+        args.scrn_list.clone()
+    };
+    let scrn_list = confirm_drops(catalog.as_ref(), scrn_list)?;
+
+    Ok(Config::new(username, password, time, crn_list, scrn_list))
+}